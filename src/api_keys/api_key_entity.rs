@@ -0,0 +1,80 @@
+use serde::{Deserialize, Serialize};
+use service_sdk::{my_no_sql_sdk, rust_extensions::date_time::DateTimeAsMicroseconds};
+use sha2::{Digest, Sha256};
+
+use crate::session_tokens::{SessionTokenFormat, SessionTokenGenerator};
+
+// RowKey is sha256 hex of the key. Plain keys are never stored
+#[service_sdk::my_no_sql_sdk::macros::my_no_sql_entity(table_name: "apikeys")]
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiKeyEntity {
+    pub integration_id: String,
+    pub scopes: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allowed_ips: Option<Vec<String>>,
+    // Unix microseconds. Set on the old key when it is rotated
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires: Option<i64>,
+}
+
+impl ApiKeyEntity {
+    pub const PARTITION_KEY: &'static str = "k";
+
+    pub fn new(api_key: &str, integration_id: String, scopes: Vec<String>) -> Self {
+        Self {
+            partition_key: Self::PARTITION_KEY.to_string(),
+            row_key: hash_api_key(api_key),
+            time_stamp: Default::default(),
+            integration_id,
+            scopes,
+            allowed_ips: None,
+            expires: None,
+        }
+    }
+
+    pub fn get_key_hash(&self) -> &str {
+        &self.row_key
+    }
+
+    pub fn is_expired(&self, now: DateTimeAsMicroseconds) -> bool {
+        match self.expires {
+            Some(expires) => expires <= now.unix_microseconds,
+            None => false,
+        }
+    }
+
+    pub fn is_ip_allowed(&self, ip: &str) -> bool {
+        match self.allowed_ips.as_ref() {
+            Some(allowed_ips) => allowed_ips.iter().any(|allowed_ip| allowed_ip == ip),
+            None => true,
+        }
+    }
+}
+
+pub fn hash_api_key(api_key: &str) -> String {
+    hex::encode(Sha256::digest(api_key.as_bytes()))
+}
+
+// Plain key is shown to the integration once; only its hash goes to ApiKeyEntity
+pub fn generate_api_key() -> String {
+    SessionTokenGenerator::new(SessionTokenFormat {
+        body_length: 43,
+        prefix: Some("ak".to_string()),
+        with_checksum: true,
+    })
+    .generate()
+}
+
+#[cfg(test)]
+mod test {
+    use super::hash_api_key;
+
+    #[test]
+    fn test_hash_api_key() {
+        assert_eq!(
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+            hash_api_key("abc")
+        );
+    }
+}
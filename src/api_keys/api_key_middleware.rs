@@ -0,0 +1,152 @@
+use my_http_server::*;
+use service_sdk::my_http_server;
+use service_sdk::rust_extensions::date_time::DateTimeAsMicroseconds;
+use std::sync::Arc;
+
+use crate::middlewares::{continue_unauthenticated, get_header, AuthFailReason};
+
+use super::{hash_api_key, ApiKeyEntity, ApiKeyRequestCredentials, ApiKeyStore};
+
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+// Works next to AuthMiddleware: requests without X-Api-Key header are left to it.
+// Has to be registered after AuthMiddleware: requests it already authenticated are skipped,
+// and AuthMiddleware clears the fail reason this middleware records.
+// Like AuthMiddleware it does not reject requests; routes which require credentials get the reason from AuthErrorFactory
+pub struct ApiKeyMiddleware {
+    api_keys_store: Arc<dyn ApiKeyStore + Send + Sync + 'static>,
+}
+
+impl ApiKeyMiddleware {
+    pub fn new(api_keys_store: Arc<dyn ApiKeyStore + Send + Sync + 'static>) -> Self {
+        Self { api_keys_store }
+    }
+
+    pub async fn authenticate(
+        &self,
+        api_key: &str,
+        request_ip: &str,
+        now: DateTimeAsMicroseconds,
+    ) -> Result<Arc<ApiKeyEntity>, AuthFailReason> {
+        let key_hash = hash_api_key(api_key);
+
+        let Some(api_key) = self.api_keys_store.get_api_key(&key_hash).await else {
+            return Err(AuthFailReason::TokenIsInvalid);
+        };
+
+        if api_key.is_expired(now) {
+            return Err(AuthFailReason::TokenExpired);
+        }
+
+        if !api_key.is_ip_allowed(request_ip) {
+            return Err(AuthFailReason::ApiKeyIpIsNotAllowed);
+        }
+
+        Ok(api_key)
+    }
+}
+
+#[async_trait::async_trait]
+impl HttpServerMiddleware for ApiKeyMiddleware {
+    async fn handle_request(
+        &self,
+        ctx: &mut HttpContext,
+    ) -> Option<Result<HttpOkResult, HttpFailResult>> {
+        if ctx.credentials.is_some() {
+            return None;
+        }
+
+        let api_key = match get_header(ctx, API_KEY_HEADER) {
+            Ok(api_key) => api_key?.trim(),
+            Err(_) => return continue_unauthenticated(AuthFailReason::TokenIsInvalid),
+        };
+
+        let request_ip = ctx.request.get_ip();
+
+        let api_key = match self
+            .authenticate(
+                api_key,
+                request_ip.get_real_ip(),
+                DateTimeAsMicroseconds::now(),
+            )
+            .await
+        {
+            Ok(api_key) => api_key,
+            Err(reason) => return continue_unauthenticated(reason),
+        };
+
+        ctx.credentials = Some(Box::new(ApiKeyRequestCredentials::new(api_key)));
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{sync::Arc, time::Duration};
+
+    use service_sdk::rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use super::ApiKeyMiddleware;
+    use crate::api_keys::{ApiKeyEntity, InMemoryApiKeyStore};
+    use crate::middlewares::AuthFailReason;
+
+    fn create_middleware() -> ApiKeyMiddleware {
+        let store = InMemoryApiKeyStore::new();
+
+        store.insert(ApiKeyEntity::new("valid", "partner".to_string(), vec![]));
+
+        let mut api_key = ApiKeyEntity::new("ip-bound", "partner".to_string(), vec![]);
+        api_key.allowed_ips = Some(vec!["10.0.0.1".to_string()]);
+        store.insert(api_key);
+
+        let mut api_key = ApiKeyEntity::new("rotated", "partner".to_string(), vec![]);
+        api_key.expires = Some(DateTimeAsMicroseconds::now().unix_microseconds);
+        store.insert(api_key);
+
+        ApiKeyMiddleware::new(Arc::new(store))
+    }
+
+    #[tokio::test]
+    async fn test_authenticate() {
+        let middleware = create_middleware();
+        let now = DateTimeAsMicroseconds::now().add(Duration::from_secs(1));
+
+        let api_key = middleware.authenticate("valid", "10.0.0.2", now).await;
+        assert_eq!(api_key.unwrap().integration_id, "partner");
+
+        assert!(middleware
+            .authenticate("ip-bound", "10.0.0.1", now)
+            .await
+            .is_ok());
+
+        assert_eq!(
+            middleware
+                .authenticate("ip-bound", "10.0.0.2", now)
+                .await
+                .err(),
+            Some(AuthFailReason::ApiKeyIpIsNotAllowed)
+        );
+
+        assert_eq!(
+            middleware
+                .authenticate("rotated", "10.0.0.1", now)
+                .await
+                .err(),
+            Some(AuthFailReason::TokenExpired)
+        );
+
+        assert_eq!(
+            middleware
+                .authenticate("unknown", "10.0.0.1", now)
+                .await
+                .err(),
+            Some(AuthFailReason::TokenIsInvalid)
+        );
+    }
+
+    #[test]
+    fn test_ip_not_allowed_is_forbidden() {
+        assert_eq!(AuthFailReason::ApiKeyIpIsNotAllowed.get_http_code(), 403);
+    }
+}
@@ -0,0 +1,41 @@
+use std::sync::Arc;
+
+use service_sdk::my_http_server;
+use service_sdk::my_http_server::{RequestClaim, RequestCredentials};
+use service_sdk::rust_extensions::date_time::DateTimeAsMicroseconds;
+
+use super::ApiKeyEntity;
+
+// get_id returns integration id, not a trader id
+pub struct ApiKeyRequestCredentials {
+    pub api_key: Arc<ApiKeyEntity>,
+}
+
+impl ApiKeyRequestCredentials {
+    pub fn new(api_key: Arc<ApiKeyEntity>) -> Self {
+        Self { api_key }
+    }
+}
+
+impl RequestCredentials for ApiKeyRequestCredentials {
+    fn get_id(&self) -> &str {
+        &self.api_key.integration_id
+    }
+
+    fn get_claims<'s>(&'s self) -> Option<Vec<my_http_server::RequestClaim<'s>>> {
+        let expires = DateTimeAsMicroseconds::new(self.api_key.expires.unwrap_or(i64::MAX));
+
+        let result = self
+            .api_key
+            .scopes
+            .iter()
+            .map(|scope| RequestClaim {
+                id: scope,
+                expires,
+                allowed_ips: self.api_key.allowed_ips.as_ref(),
+            })
+            .collect();
+
+        Some(result)
+    }
+}
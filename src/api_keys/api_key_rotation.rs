@@ -0,0 +1,93 @@
+use std::time::Duration;
+
+use service_sdk::rust_extensions::date_time::DateTimeAsMicroseconds;
+
+use crate::middlewares::duration_as_micros;
+
+use super::{ApiKeyEntity, ApiKeyWriter};
+
+// Old keys of the integration stay valid for `overlap` so clients can switch without downtime.
+// The new key is saved first, so a failed rotation never leaves the integration without a valid key
+pub async fn rotate_api_key(
+    api_key_writer: &(dyn ApiKeyWriter + Send + Sync),
+    new_api_key: ApiKeyEntity,
+    overlap: Duration,
+) -> Result<(), String> {
+    let expires = DateTimeAsMicroseconds::now()
+        .unix_microseconds
+        .saturating_add(duration_as_micros(overlap));
+
+    let old_api_keys = api_key_writer
+        .get_integration_api_keys(&new_api_key.integration_id)
+        .await?;
+
+    let new_key_hash = new_api_key.get_key_hash().to_string();
+
+    api_key_writer.save_api_key(new_api_key).await?;
+
+    for mut old_api_key in old_api_keys {
+        if old_api_key.get_key_hash() == new_key_hash {
+            continue;
+        }
+
+        if let Some(current_expires) = old_api_key.expires {
+            if current_expires <= expires {
+                continue;
+            }
+        }
+
+        old_api_key.expires = Some(expires);
+        api_key_writer.save_api_key(old_api_key).await?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use service_sdk::rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use super::rotate_api_key;
+    use crate::api_keys::{hash_api_key, ApiKeyEntity, ApiKeyStore, InMemoryApiKeyStore};
+
+    #[tokio::test]
+    async fn test_old_key_stays_valid_for_overlap() {
+        let store = InMemoryApiKeyStore::new();
+        store.insert(ApiKeyEntity::new("old", "partner".to_string(), vec![]));
+        store.insert(ApiKeyEntity::new("other", "other".to_string(), vec![]));
+
+        let new_api_key = ApiKeyEntity::new("new", "partner".to_string(), vec![]);
+        rotate_api_key(&store, new_api_key, Duration::from_secs(60))
+            .await
+            .unwrap();
+
+        let now = DateTimeAsMicroseconds::now();
+        let old_api_key = store.get_api_key(&hash_api_key("old")).await.unwrap();
+        assert!(!old_api_key.is_expired(now));
+        assert!(old_api_key.is_expired(now.add(Duration::from_secs(61))));
+
+        let new_api_key = store.get_api_key(&hash_api_key("new")).await.unwrap();
+        assert_eq!(new_api_key.expires, None);
+
+        let other_api_key = store.get_api_key(&hash_api_key("other")).await.unwrap();
+        assert_eq!(other_api_key.expires, None);
+    }
+
+    #[tokio::test]
+    async fn test_rotation_does_not_extend_expiration() {
+        let store = InMemoryApiKeyStore::new();
+        let mut old_api_key = ApiKeyEntity::new("old", "partner".to_string(), vec![]);
+        old_api_key.expires = Some(1);
+        store.insert(old_api_key);
+
+        let new_api_key = ApiKeyEntity::new("new", "partner".to_string(), vec![]);
+        rotate_api_key(&store, new_api_key, Duration::from_secs(60))
+            .await
+            .unwrap();
+
+        let old_api_key = store.get_api_key(&hash_api_key("old")).await.unwrap();
+        assert_eq!(old_api_key.expires, Some(1));
+    }
+}
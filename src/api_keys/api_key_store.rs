@@ -0,0 +1,8 @@
+use std::sync::Arc;
+
+use super::ApiKeyEntity;
+
+#[async_trait::async_trait]
+pub trait ApiKeyStore {
+    async fn get_api_key(&self, key_hash: &str) -> Option<Arc<ApiKeyEntity>>;
+}
@@ -0,0 +1,11 @@
+use super::ApiKeyEntity;
+
+#[async_trait::async_trait]
+pub trait ApiKeyWriter {
+    async fn get_integration_api_keys(
+        &self,
+        integration_id: &str,
+    ) -> Result<Vec<ApiKeyEntity>, String>;
+
+    async fn save_api_key(&self, api_key: ApiKeyEntity) -> Result<(), String>;
+}
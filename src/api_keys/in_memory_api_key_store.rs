@@ -0,0 +1,57 @@
+use std::{collections::HashMap, sync::Arc, sync::Mutex};
+
+use super::{ApiKeyEntity, ApiKeyStore, ApiKeyWriter};
+
+pub struct InMemoryApiKeyStore {
+    keys: Mutex<HashMap<String, Arc<ApiKeyEntity>>>,
+}
+
+impl InMemoryApiKeyStore {
+    pub fn new() -> Self {
+        Self {
+            keys: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn insert(&self, api_key: ApiKeyEntity) {
+        let mut keys = self.keys.lock().unwrap();
+        keys.insert(api_key.get_key_hash().to_string(), Arc::new(api_key));
+    }
+}
+
+impl Default for InMemoryApiKeyStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait::async_trait]
+impl ApiKeyStore for InMemoryApiKeyStore {
+    async fn get_api_key(&self, key_hash: &str) -> Option<Arc<ApiKeyEntity>> {
+        let keys = self.keys.lock().unwrap();
+        keys.get(key_hash).cloned()
+    }
+}
+
+#[async_trait::async_trait]
+impl ApiKeyWriter for InMemoryApiKeyStore {
+    async fn get_integration_api_keys(
+        &self,
+        integration_id: &str,
+    ) -> Result<Vec<ApiKeyEntity>, String> {
+        let keys = self.keys.lock().unwrap();
+
+        let result = keys
+            .values()
+            .filter(|api_key| api_key.integration_id == integration_id)
+            .map(|api_key| api_key.as_ref().clone())
+            .collect();
+
+        Ok(result)
+    }
+
+    async fn save_api_key(&self, api_key: ApiKeyEntity) -> Result<(), String> {
+        self.insert(api_key);
+        Ok(())
+    }
+}
@@ -0,0 +1,18 @@
+mod api_key_entity;
+pub use api_key_entity::*;
+mod api_key_store;
+pub use api_key_store::*;
+mod api_key_writer;
+pub use api_key_writer::*;
+mod api_key_rotation;
+pub use api_key_rotation::*;
+mod in_memory_api_key_store;
+pub use in_memory_api_key_store::*;
+mod api_key_request_credentials;
+#[cfg(feature = "auth-middleware")]
+mod my_no_sql_api_key_store;
+#[cfg(feature = "session-writer")]
+mod my_no_sql_api_key_writer;
+pub use api_key_request_credentials::*;
+mod api_key_middleware;
+pub use api_key_middleware::*;
@@ -0,0 +1,12 @@
+use std::sync::Arc;
+
+use service_sdk::my_no_sql_sdk::reader::MyNoSqlDataReaderTcp;
+
+use super::{ApiKeyEntity, ApiKeyStore};
+
+#[async_trait::async_trait]
+impl ApiKeyStore for MyNoSqlDataReaderTcp<ApiKeyEntity> {
+    async fn get_api_key(&self, key_hash: &str) -> Option<Arc<ApiKeyEntity>> {
+        self.get_entity(ApiKeyEntity::PARTITION_KEY, key_hash).await
+    }
+}
@@ -0,0 +1,30 @@
+use service_sdk::my_no_sql_sdk::data_writer::MyNoSqlDataWriter;
+
+use super::{ApiKeyEntity, ApiKeyWriter};
+
+#[async_trait::async_trait]
+impl ApiKeyWriter for MyNoSqlDataWriter<ApiKeyEntity> {
+    async fn get_integration_api_keys(
+        &self,
+        integration_id: &str,
+    ) -> Result<Vec<ApiKeyEntity>, String> {
+        let result = self
+            .get_by_partition_key(ApiKeyEntity::PARTITION_KEY, None)
+            .await
+            .map_err(|err| format!("{:?}", err))?;
+
+        let result = result
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|api_key| api_key.integration_id == integration_id)
+            .collect();
+
+        Ok(result)
+    }
+
+    async fn save_api_key(&self, api_key: ApiKeyEntity) -> Result<(), String> {
+        self.insert_or_replace_entity(&api_key)
+            .await
+            .map_err(|err| format!("{:?}", err))
+    }
+}
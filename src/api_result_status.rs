@@ -5,96 +5,394 @@ use service_sdk::my_http_server::controllers::documentation::DataTypeProvider;
 use service_sdk::my_http_server::macros::{MyHttpIntegerEnum, MyHttpObjectStructure};
 use service_sdk::my_http_server::*;
 
-#[derive(Serialize_repr, Deserialize_repr, MyHttpIntegerEnum, Debug, Clone, Copy)]
-#[repr(i16)]
-pub enum ApiResultStatus {
-    #[http_enum_case(id="0"; description="Operations was successful")]
-    Ok,
-
-    #[http_enum_case(id="-1"; description="AccessTokenInvalid")]
-    TokenIsInvalid = -1,
-
-    #[http_enum_case(id="-2"; description="AccessTokenExpired")]
-    AccessTokenExpired = -2,
-
-    #[http_enum_case(id="-3"; description="Invalid username or password")]
-    InvalidUserNameOrPassword = -3,
+use crate::error_responses::{legacy_fail_result, ErrorResponse, ProblemDetails};
+use crate::{ResultStatus, ResultStatusRange, ServiceResultStatus};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApiResultStatusCategory {
+    Success,
+    Authentication,
+    Authorization,
+    Validation,
+    Business,
+    Client,
+    Server,
+}
 
-    #[http_enum_case(id="-4"; description="User exists")]
-    UserExists = -4,
+#[derive(Debug, Clone, Copy)]
+pub struct ApiResultStatusMetadata {
+    pub id: &'static str,
+    pub description: &'static str,
+    pub http_code: u16,
+    pub write_to_telemetry: bool,
+    pub retryable: bool,
+    pub category: ApiResultStatusCategory,
+}
 
-    #[http_enum_case(id="-5"; description="User not found")]
-    UserNotFound = -5,
+// Every status is declared exactly once here. The enum, its swagger cases and all metadata
+// are generated from this table, so a status can not be added without all of its fields.
+// The serialized value is parsed from the swagger id, so the two can not diverge.
+macro_rules! api_result_statuses {
+    ($(
+        $variant:ident {
+            id: $id:literal,
+            description: $description:literal,
+            http_code: $http_code:literal,
+            telemetry: $telemetry:literal,
+            retryable: $retryable:literal,
+            category: $category:ident $(,)?
+        }
+    ),* $(,)?) => {
+        #[derive(Serialize_repr, Deserialize_repr, MyHttpIntegerEnum, Debug, Clone, Copy)]
+        #[repr(i16)]
+        pub enum ApiResultStatus {
+            $(
+                #[http_enum_case(id=$id; description=$description)]
+                $variant = parse_status_id($id),
+            )*
+        }
 
-    #[http_enum_case(id="-6"; description="Old password is wrong")]
-    OldPasswordIsWrong = -6,
+        impl ApiResultStatus {
+            pub const ALL: &'static [ApiResultStatus] = &[$(ApiResultStatus::$variant),*];
+
+            pub fn get_metadata(&self) -> &'static ApiResultStatusMetadata {
+                match self {
+                    $(
+                        ApiResultStatus::$variant => &ApiResultStatusMetadata {
+                            id: $id,
+                            description: $description,
+                            http_code: $http_code,
+                            write_to_telemetry: $telemetry,
+                            retryable: $retryable,
+                            category: ApiResultStatusCategory::$category,
+                        },
+                    )*
+                }
+            }
+        }
+    };
+}
 
-    #[http_enum_case(id="-7"; description="Wrong file extension")]
-    WrongFileExtension = -7,
+const fn parse_status_id(src: &str) -> i16 {
+    let bytes = src.as_bytes();
+    let (negative, mut i) = if !bytes.is_empty() && bytes[0] == b'-' {
+        (true, 1)
+    } else {
+        (false, 0)
+    };
+
+    assert!(i < bytes.len(), "status id is empty");
+
+    let mut result: i16 = 0;
+    while i < bytes.len() {
+        let digit = bytes[i];
+        assert!(digit.is_ascii_digit(), "status id must be a number");
+        result = result * 10 + (digit - b'0') as i16;
+        i += 1;
+    }
 
-    #[http_enum_case(id="-8"; description="Crypto deposit is not supported")]
-    CryptoDepositIsNotSupported = -8,
+    if negative {
+        -result
+    } else {
+        result
+    }
+}
 
-    #[http_enum_case(id="-9"; description="Personal data is not valid")]
-    PersonalDataNotValid = -9,
+api_result_statuses! {
+    Ok {
+        id: "0",
+        description: "Operations was successful",
+        http_code: 200,
+        telemetry: true,
+        retryable: false,
+        category: Success,
+    },
+
+    TokenIsInvalid {
+        id: "-1",
+        description: "AccessTokenInvalid",
+        http_code: 401,
+        telemetry: false,
+        retryable: false,
+        category: Authentication,
+    },
+
+    AccessTokenExpired {
+        id: "-2",
+        description: "AccessTokenExpired",
+        http_code: 401,
+        telemetry: false,
+        retryable: false,
+        category: Authentication,
+    },
+
+    InvalidUserNameOrPassword {
+        id: "-3",
+        description: "Invalid username or password",
+        http_code: 200,
+        telemetry: false,
+        retryable: false,
+        category: Authentication,
+    },
+
+    UserExists {
+        id: "-4",
+        description: "User exists",
+        http_code: 200,
+        telemetry: false,
+        retryable: false,
+        category: Business,
+    },
+
+    UserNotFound {
+        id: "-5",
+        description: "User not found",
+        http_code: 200,
+        telemetry: false,
+        retryable: false,
+        category: Business,
+    },
+
+    OldPasswordIsWrong {
+        id: "-6",
+        description: "Old password is wrong",
+        http_code: 200,
+        telemetry: false,
+        retryable: false,
+        category: Validation,
+    },
+
+    WrongFileExtension {
+        id: "-7",
+        description: "Wrong file extension",
+        http_code: 200,
+        telemetry: false,
+        retryable: false,
+        category: Validation,
+    },
+
+    CryptoDepositIsNotSupported {
+        id: "-8",
+        description: "Crypto deposit is not supported",
+        http_code: 200,
+        telemetry: false,
+        retryable: false,
+        category: Business,
+    },
+
+    PersonalDataNotValid {
+        id: "-9",
+        description: "Personal data is not valid",
+        http_code: 200,
+        telemetry: false,
+        retryable: false,
+        category: Validation,
+    },
+
+    NotEnoughFunds {
+        id: "-10",
+        description: "Not enough funds",
+        http_code: 200,
+        telemetry: false,
+        retryable: false,
+        category: Business,
+    },
+
+    CountryIsRestricted {
+        id: "-11",
+        description: "CountryRestriction",
+        http_code: 200,
+        telemetry: false,
+        retryable: false,
+        category: Authorization,
+    },
+
+    ExchangeQuoteIsExpired {
+        id: "-12",
+        description: "Exchange quote is expired",
+        http_code: 200,
+        telemetry: false,
+        retryable: false,
+        category: Business,
+    },
+
+    NoLiquidity {
+        id: "-13",
+        description: "No liquidity",
+        http_code: 200,
+        telemetry: false,
+        retryable: true,
+        category: Business,
+    },
+
+    RecaptchaVerificationFail {
+        id: "-14",
+        description: "Recaptcha verification fail",
+        http_code: 401,
+        telemetry: false,
+        retryable: false,
+        category: Authentication,
+    },
+
+    ExchangeBetweenAssetsIsDisabled {
+        id: "-15",
+        description: "Exchange between assets is disabled",
+        http_code: 200,
+        telemetry: true,
+        retryable: false,
+        category: Business,
+    },
+
+    PasswordRecoveryCodeIsInvalid {
+        id: "-16",
+        description: "Password recovery code is invalid",
+        http_code: 200,
+        telemetry: false,
+        retryable: false,
+        category: Validation,
+    },
+
+    TwoFaCodeIsInvalid {
+        id: "-17",
+        description: "2Fa code is invalid",
+        http_code: 200,
+        telemetry: false,
+        retryable: false,
+        category: Authentication,
+    },
+
+    AccessTokenMissing {
+        id: "-18",
+        description: "Access token is missing",
+        http_code: 401,
+        telemetry: false,
+        retryable: false,
+        category: Authentication,
+    },
+
+    SessionsLimitReached {
+        id: "-19",
+        description: "Sessions limit reached",
+        http_code: 200,
+        telemetry: false,
+        retryable: false,
+        category: Business,
+    },
+
+    CsrfTokenIsInvalid {
+        id: "-20",
+        description: "CSRF token is invalid",
+        http_code: 403,
+        telemetry: false,
+        retryable: false,
+        category: Authorization,
+    },
+
+    SignatureIsInvalid {
+        id: "-21",
+        description: "Request signature is invalid",
+        http_code: 401,
+        telemetry: false,
+        retryable: false,
+        category: Authentication,
+    },
+
+    ApiKeyIpIsNotAllowed {
+        id: "-22",
+        description: "Api key is not allowed from this ip",
+        http_code: 403,
+        telemetry: false,
+        retryable: false,
+        category: Authorization,
+    },
+
+    TwoFaAttemptsLimitReached {
+        id: "-23",
+        description: "2Fa attempts limit reached",
+        http_code: 200,
+        telemetry: false,
+        retryable: false,
+        category: Authentication,
+    },
+
+    InternalError {
+        id: "-24",
+        description: "Request could not be processed",
+        http_code: 500,
+        telemetry: true,
+        retryable: true,
+        category: Server,
+    },
+
+    AccessClaimRequired {
+        id: "-998",
+        description: "Access claim required",
+        http_code: 403,
+        telemetry: false,
+        retryable: false,
+        category: Authorization,
+    },
+
+    ForceUpdateIsRequired {
+        id: "-999",
+        description: "Force Update required",
+        http_code: 200,
+        telemetry: false,
+        retryable: false,
+        category: Client,
+    },
+}
 
-    #[http_enum_case(id="-10"; description="Not enough funds")]
-    NotEnoughFunds = -10,
+impl ApiResultStatus {
+    pub fn get_id(&self) -> &'static str {
+        self.get_metadata().id
+    }
 
-    #[http_enum_case(id="-11"; description="CountryRestriction")]
-    CountryIsRestricted = -11,
+    pub fn get_description(&self) -> &'static str {
+        self.get_metadata().description
+    }
 
-    #[http_enum_case(id="-12"; description="Exchange quote is expired")]
-    ExchangeQuoteIsExpired = -12,
+    pub fn get_status_code(&self) -> u16 {
+        self.get_metadata().http_code
+    }
 
-    #[http_enum_case(id="-13"; description="No liquidity")]
-    NoLiquidity = -13,
+    pub fn write_to_telemetry(&self) -> bool {
+        self.get_metadata().write_to_telemetry
+    }
 
-    #[http_enum_case(id="-14"; description="Recaptcha verification fail")]
-    RecaptchaVerificationFail = -14,
+    pub fn is_retryable(&self) -> bool {
+        self.get_metadata().retryable
+    }
 
-    #[http_enum_case(id="-15"; description="Exchange between assets is disabled")]
-    ExchangeBetweenAssetsIsDisabled = -15,
+    pub fn get_category(&self) -> ApiResultStatusCategory {
+        self.get_metadata().category
+    }
+}
 
-    #[http_enum_case(id="-16"; description="Password recovery code is invalid")]
-    PasswordRecoveryCodeIsInvalid = -16,
+impl ResultStatus for ApiResultStatus {
+    fn get_value(&self) -> i16 {
+        *self as i16
+    }
 
-    #[http_enum_case(id="-17"; description="2Fa code is invalid")]
-    TwoFaCodeIsInvalid = -17,
+    fn get_description(&self) -> &'static str {
+        self.get_metadata().description
+    }
 
-    #[http_enum_case(id="-998"; description="Access claim required")]
-    AccessClaimRequired = -998,
+    fn get_status_code(&self) -> u16 {
+        self.get_metadata().http_code
+    }
 
-    #[http_enum_case(id="-999"; description="Force Update required")]
-    ForceUpdateIsRequired = -999,
+    fn write_to_telemetry(&self) -> bool {
+        self.get_metadata().write_to_telemetry
+    }
 }
 
-impl ApiResultStatus {
-    pub fn get_status_code(&self) -> u16 {
-        match self {
-            ApiResultStatus::Ok => 200,
-            ApiResultStatus::InvalidUserNameOrPassword => 200,
-            ApiResultStatus::UserExists => 200,
-            ApiResultStatus::UserNotFound => 200,
-            ApiResultStatus::OldPasswordIsWrong => 200,
-            ApiResultStatus::WrongFileExtension => 200,
-            ApiResultStatus::PersonalDataNotValid => 200,
-
-            ApiResultStatus::AccessTokenExpired => 401,
-            ApiResultStatus::TokenIsInvalid => 401,
-
-            ApiResultStatus::RecaptchaVerificationFail => 401,
-            ApiResultStatus::CountryIsRestricted => 200,
-            ApiResultStatus::ForceUpdateIsRequired => 200,
-            ApiResultStatus::NotEnoughFunds => 200,
-            ApiResultStatus::ExchangeQuoteIsExpired => 200,
-            ApiResultStatus::NoLiquidity => 200,
-            ApiResultStatus::AccessClaimRequired => 403,
-            ApiResultStatus::CryptoDepositIsNotSupported => 200,
-            ApiResultStatus::ExchangeBetweenAssetsIsDisabled => 200,
-            ApiResultStatus::PasswordRecoveryCodeIsInvalid => 200,
-            ApiResultStatus::TwoFaCodeIsInvalid => 200,
-        }
+impl ServiceResultStatus for ApiResultStatus {
+    const SERVICE_NAME: &'static str = "shared";
+    const RANGE: ResultStatusRange = ResultStatusRange::new(-999, 0);
+
+    fn get_all() -> &'static [Self] {
+        Self::ALL
     }
 }
 
@@ -103,26 +401,43 @@ pub struct ApiHttpResult {
     pub status: ApiResultStatus,
 }
 
+impl ErrorResponse for ApiHttpResult {
+    fn get_result_status(&self) -> i16 {
+        self.status.get_value()
+    }
+
+    fn into_legacy_fail_result(self, message: Option<&str>) -> HttpFailResult {
+        into_http_fail_result(&self, &self.status, message)
+    }
+
+    fn into_problem_details(self) -> ProblemDetails {
+        ProblemDetails::new(&self.status)
+    }
+}
+
 impl Into<HttpFailResult> for ApiHttpResult {
     fn into(self) -> HttpFailResult {
-        self.status.into()
+        self.into_fail_result()
     }
 }
 
-impl Into<HttpFailResult> for ApiResultStatus {
-    fn into(self) -> HttpFailResult {
-        let status_code = self.get_status_code();
-        let result = ApiHttpResult { status: self };
+impl ErrorResponse for ApiResultStatus {
+    fn get_result_status(&self) -> i16 {
+        *self as i16
+    }
 
-        let write_to_telemetry = write_to_telemetry(&self);
+    fn into_legacy_fail_result(self, message: Option<&str>) -> HttpFailResult {
+        ApiHttpResult { status: self }.into_legacy_fail_result(message)
+    }
 
-        HttpFailResult::new(
-            my_http_server::WebContentType::Json,
-            status_code,
-            serde_json::to_vec(&result).unwrap(),
-            write_to_telemetry,
-            write_to_telemetry,
-        )
+    fn into_problem_details(self) -> ProblemDetails {
+        ProblemDetails::new(&self)
+    }
+}
+
+impl Into<HttpFailResult> for ApiResultStatus {
+    fn into(self) -> HttpFailResult {
+        self.into_fail_result()
     }
 }
 
@@ -132,53 +447,50 @@ pub struct ApiHttpResultWithData<TData: Serialize + DataTypeProvider> {
     pub data: Option<TData>,
 }
 
-impl<TData: Serialize + DataTypeProvider> Into<HttpFailResult> for ApiHttpResultWithData<TData> {
-    fn into(self) -> HttpFailResult {
-        let status_code = self.status.get_status_code();
-
-        let write_to_telemetry = write_to_telemetry(&self.status);
+impl<TData: Serialize + DataTypeProvider> ErrorResponse for ApiHttpResultWithData<TData> {
+    fn get_result_status(&self) -> i16 {
+        self.status.get_value()
+    }
 
-        HttpFailResult::new(
-            my_http_server::WebContentType::Json,
-            status_code,
-            serde_json::to_vec(&self).unwrap(),
-            write_to_telemetry,
-            write_to_telemetry,
-        )
+    fn into_legacy_fail_result(self, message: Option<&str>) -> HttpFailResult {
+        into_http_fail_result(&self, &self.status, message)
     }
-}
 
-fn write_to_telemetry(from: &ApiResultStatus) -> bool {
-    match from {
-        ApiResultStatus::Ok => true,
-        ApiResultStatus::InvalidUserNameOrPassword => false,
-        ApiResultStatus::UserExists => false,
-        ApiResultStatus::UserNotFound => false,
-        ApiResultStatus::OldPasswordIsWrong => false,
-        ApiResultStatus::WrongFileExtension => false,
-        ApiResultStatus::PersonalDataNotValid => false,
+    fn into_problem_details(self) -> ProblemDetails {
+        let result = ProblemDetails::new(&self.status);
 
-        ApiResultStatus::AccessTokenExpired => false,
-        ApiResultStatus::RecaptchaVerificationFail => false,
-        ApiResultStatus::CountryIsRestricted => false,
+        match &self.data {
+            Some(data) => result.with_data(data),
+            None => result,
+        }
+    }
+}
 
-        ApiResultStatus::ForceUpdateIsRequired => false,
-        ApiResultStatus::NotEnoughFunds => false,
-        ApiResultStatus::ExchangeQuoteIsExpired => false,
-        ApiResultStatus::NoLiquidity => false,
-        ApiResultStatus::AccessClaimRequired => false,
-        ApiResultStatus::CryptoDepositIsNotSupported => false,
-        ApiResultStatus::TokenIsInvalid => false,
-        ApiResultStatus::ExchangeBetweenAssetsIsDisabled => true,
-        ApiResultStatus::PasswordRecoveryCodeIsInvalid => false,
-        ApiResultStatus::TwoFaCodeIsInvalid => false,
+impl<TData: Serialize + DataTypeProvider> Into<HttpFailResult> for ApiHttpResultWithData<TData> {
+    fn into(self) -> HttpFailResult {
+        self.into_fail_result()
     }
 }
 
+pub(crate) fn into_http_fail_result(
+    body: &impl Serialize,
+    status: &impl ResultStatus,
+    message: Option<&str>,
+) -> HttpFailResult {
+    legacy_fail_result(
+        body,
+        status.get_status_code(),
+        status.write_to_telemetry(),
+        message,
+    )
+}
+
 #[cfg(test)]
 mod test {
-    use super::ApiResultStatus;
+    use super::{ApiResultStatus, ApiResultStatusCategory};
     use serde::Serialize;
+    use std::collections::HashSet;
+
     #[derive(Serialize, Debug)]
     pub struct TestStruct {
         result: ApiResultStatus,
@@ -194,4 +506,101 @@ mod test {
 
         println!("{}", result);
     }
+
+    #[test]
+    pub fn test_ids_are_unique_and_match_values() {
+        let mut ids = HashSet::new();
+
+        for status in ApiResultStatus::ALL {
+            assert!(ids.insert(status.get_id()), "{:?}", status);
+            assert_eq!(status.get_id(), (*status as i16).to_string());
+            assert!(!status.get_description().is_empty(), "{:?}", status);
+        }
+    }
+
+    #[test]
+    pub fn test_values_are_parsed_from_ids() {
+        assert_eq!(ApiResultStatus::Ok as i16, 0);
+        assert_eq!(ApiResultStatus::TokenIsInvalid as i16, -1);
+        assert_eq!(ApiResultStatus::ForceUpdateIsRequired as i16, -999);
+        assert_eq!(
+            "-998",
+            serde_json::to_string(&ApiResultStatus::AccessClaimRequired).unwrap()
+        );
+    }
+
+    #[test]
+    pub fn test_only_ok_is_a_success() {
+        for status in ApiResultStatus::ALL {
+            let is_ok = matches!(status, ApiResultStatus::Ok);
+            assert_eq!(
+                status.get_category() == ApiResultStatusCategory::Success,
+                is_ok
+            );
+        }
+    }
+
+    // (status, value, http code, telemetry). Statuses down to -17 and -998, -999 keep the values
+    // of the hand-written tables they were generated from; clients depend on them
+    const GOLDEN: [(ApiResultStatus, i16, u16, bool); 27] = [
+        (ApiResultStatus::Ok, 0, 200, true),
+        (ApiResultStatus::TokenIsInvalid, -1, 401, false),
+        (ApiResultStatus::AccessTokenExpired, -2, 401, false),
+        (ApiResultStatus::InvalidUserNameOrPassword, -3, 200, false),
+        (ApiResultStatus::UserExists, -4, 200, false),
+        (ApiResultStatus::UserNotFound, -5, 200, false),
+        (ApiResultStatus::OldPasswordIsWrong, -6, 200, false),
+        (ApiResultStatus::WrongFileExtension, -7, 200, false),
+        (ApiResultStatus::CryptoDepositIsNotSupported, -8, 200, false),
+        (ApiResultStatus::PersonalDataNotValid, -9, 200, false),
+        (ApiResultStatus::NotEnoughFunds, -10, 200, false),
+        (ApiResultStatus::CountryIsRestricted, -11, 200, false),
+        (ApiResultStatus::ExchangeQuoteIsExpired, -12, 200, false),
+        (ApiResultStatus::NoLiquidity, -13, 200, false),
+        (ApiResultStatus::RecaptchaVerificationFail, -14, 401, false),
+        (
+            ApiResultStatus::ExchangeBetweenAssetsIsDisabled,
+            -15,
+            200,
+            true,
+        ),
+        (
+            ApiResultStatus::PasswordRecoveryCodeIsInvalid,
+            -16,
+            200,
+            false,
+        ),
+        (ApiResultStatus::TwoFaCodeIsInvalid, -17, 200, false),
+        (ApiResultStatus::AccessClaimRequired, -998, 403, false),
+        (ApiResultStatus::ForceUpdateIsRequired, -999, 200, false),
+        // Added later
+        (ApiResultStatus::AccessTokenMissing, -18, 401, false),
+        (ApiResultStatus::SessionsLimitReached, -19, 200, false),
+        (ApiResultStatus::CsrfTokenIsInvalid, -20, 403, false),
+        (ApiResultStatus::SignatureIsInvalid, -21, 401, false),
+        (ApiResultStatus::ApiKeyIpIsNotAllowed, -22, 403, false),
+        (ApiResultStatus::TwoFaAttemptsLimitReached, -23, 200, false),
+        (ApiResultStatus::InternalError, -24, 500, true),
+    ];
+
+    #[test]
+    pub fn test_statuses_match_golden_table() {
+        assert_eq!(GOLDEN.len(), ApiResultStatus::ALL.len());
+
+        for (status, value, http_code, telemetry) in GOLDEN {
+            assert_eq!(status as i16, value, "{:?}", status);
+            assert_eq!(status.get_status_code(), http_code, "{:?}", status);
+            assert_eq!(status.write_to_telemetry(), telemetry, "{:?}", status);
+        }
+
+        for status in ApiResultStatus::ALL {
+            assert!(
+                GOLDEN
+                    .iter()
+                    .any(|(itm, _, _, _)| *itm as i16 == *status as i16),
+                "{:?}",
+                status
+            );
+        }
+    }
 }
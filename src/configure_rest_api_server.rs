@@ -2,16 +2,36 @@ use std::sync::Arc;
 
 use service_sdk::{
     my_http_server::controllers::{ControllersAuthorization, RequiredClaims},
-    my_no_sql_sdk::reader::MyNoSqlDataReaderTcp,
     HttpServerBuilder,
 };
 
-use crate::middlewares::{AuthFailResponseFactory, AuthMiddleware, SessionEntity};
+use crate::{
+    error_responses::{ErrorResponseMiddleware, ErrorResponseSettings},
+    middlewares::{AuthFailResponseFactory, AuthMiddleware},
+    session_store::SessionStore,
+};
 
 pub fn configure_rest_api_server(
     http_server_builder: &mut HttpServerBuilder,
-    sessions_reader: Arc<MyNoSqlDataReaderTcp<SessionEntity>>,
+    sessions_store: Arc<dyn SessionStore + Send + Sync + 'static>,
+    error_response_settings: ErrorResponseSettings,
+) {
+    configure_rest_api_server_with_auth_middleware(
+        http_server_builder,
+        AuthMiddleware::new(sessions_store),
+        error_response_settings,
+    );
+}
+
+pub fn configure_rest_api_server_with_auth_middleware(
+    http_server_builder: &mut HttpServerBuilder,
+    auth_middleware: AuthMiddleware,
+    error_response_settings: ErrorResponseSettings,
 ) {
+    http_server_builder.add_middleware(Arc::new(ErrorResponseMiddleware::new(
+        error_response_settings,
+    )));
+
     http_server_builder.set_authorization(ControllersAuthorization::BearerAuthentication {
         global: true,
         global_claims: RequiredClaims::no_claims(),
@@ -19,5 +39,5 @@ pub fn configure_rest_api_server(
 
     http_server_builder.set_auth_error_factory(AuthFailResponseFactory);
 
-    http_server_builder.add_auth_middleware(Arc::new(AuthMiddleware::new(sessions_reader)));
+    http_server_builder.add_auth_middleware(Arc::new(auth_middleware));
 }
@@ -0,0 +1,31 @@
+use std::sync::Arc;
+
+use service_sdk::{
+    my_http_server::controllers::{ControllersAuthorization, RequiredClaims},
+    HttpServerBuilder,
+};
+
+use crate::{
+    error_responses::{ErrorResponseMiddleware, ErrorResponseSettings},
+    jwt::JwtAuthMiddleware,
+    middlewares::AuthFailResponseFactory,
+};
+
+pub fn configure_rest_api_server_with_jwt(
+    http_server_builder: &mut HttpServerBuilder,
+    jwt_auth_middleware: JwtAuthMiddleware,
+    error_response_settings: ErrorResponseSettings,
+) {
+    http_server_builder.add_middleware(Arc::new(ErrorResponseMiddleware::new(
+        error_response_settings,
+    )));
+
+    http_server_builder.set_authorization(ControllersAuthorization::BearerAuthentication {
+        global: true,
+        global_claims: RequiredClaims::no_claims(),
+    });
+
+    http_server_builder.set_auth_error_factory(AuthFailResponseFactory);
+
+    http_server_builder.add_auth_middleware(Arc::new(jwt_auth_middleware));
+}
@@ -1,7 +1,19 @@
+use std::sync::Arc;
+
 use service_sdk::HttpServerBuilder;
 
-use crate::middlewares::AuthFailResponseFactory;
+use crate::{
+    error_responses::{ErrorResponseMiddleware, ErrorResponseSettings},
+    middlewares::AuthFailResponseFactory,
+};
+
+pub fn configure_rest_api_server(
+    http_server_builder: &mut HttpServerBuilder,
+    error_response_settings: ErrorResponseSettings,
+) {
+    http_server_builder.add_middleware(Arc::new(ErrorResponseMiddleware::new(
+        error_response_settings,
+    )));
 
-pub fn configure_rest_api_server(http_server_builder: &mut HttpServerBuilder) {
     http_server_builder.set_auth_error_factory(AuthFailResponseFactory);
 }
@@ -0,0 +1,71 @@
+use serde::Serialize;
+use service_sdk::my_http_server::{HttpContext, HttpFailResult, WebContentType};
+
+use super::{
+    get_negotiated_error_response, get_request_languages, ErrorResponseFormat, ProblemDetails,
+};
+
+// Error bodies which can be rendered either the legacy way or as problem+json
+pub trait ErrorResponse: Sized {
+    fn get_result_status(&self) -> i16;
+
+    fn into_legacy_fail_result(self, message: Option<&str>) -> HttpFailResult;
+
+    fn into_problem_details(self) -> ProblemDetails;
+
+    fn into_fail_result_with(
+        self,
+        format: ErrorResponseFormat,
+        message: Option<&str>,
+    ) -> HttpFailResult {
+        match format {
+            ErrorResponseFormat::Legacy => self.into_legacy_fail_result(message),
+            ErrorResponseFormat::ProblemJson => match message {
+                Some(message) => self.into_problem_details().with_message(message).into(),
+                None => self.into_problem_details().into(),
+            },
+        }
+    }
+
+    // Format and message language negotiated by ErrorResponseMiddleware for the current request
+    fn into_fail_result(self) -> HttpFailResult {
+        let negotiated = get_negotiated_error_response();
+        let message = negotiated
+            .settings
+            .get_localized_message(self.get_result_status(), &negotiated.languages);
+        self.into_fail_result_with(negotiated.format, message)
+    }
+
+    // Format and message language are negotiated by Accept and Accept-Language headers
+    fn into_fail_result_for(self, ctx: &HttpContext) -> HttpFailResult {
+        let negotiated = get_negotiated_error_response();
+        let message = negotiated
+            .settings
+            .get_localized_message(self.get_result_status(), &get_request_languages(ctx));
+        self.into_fail_result_with(
+            ErrorResponseFormat::detect(ctx, &negotiated.settings),
+            message,
+        )
+    }
+}
+
+pub(crate) fn legacy_fail_result(
+    body: &impl Serialize,
+    http_code: u16,
+    write_to_telemetry: bool,
+    message: Option<&str>,
+) -> HttpFailResult {
+    let mut body = serde_json::to_value(body).unwrap();
+
+    if let (Some(message), Some(fields)) = (message, body.as_object_mut()) {
+        fields.insert("message".to_string(), message.into());
+    }
+
+    HttpFailResult::new(
+        WebContentType::Json,
+        http_code,
+        serde_json::to_vec(&body).unwrap(),
+        write_to_telemetry,
+        write_to_telemetry,
+    )
+}
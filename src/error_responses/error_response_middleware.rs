@@ -0,0 +1,44 @@
+use my_http_server::*;
+use service_sdk::my_http_server;
+use std::sync::Arc;
+
+use super::{
+    get_request_languages, record_negotiated_error_response, ErrorResponseFormat,
+    ErrorResponseSettings, NegotiatedErrorResponse,
+};
+
+// Negotiates the error format once per request, so errors rendered without HttpContext
+// (AuthErrorFactory, Into<HttpFailResult> in controllers) follow the Accept header too
+pub struct ErrorResponseMiddleware {
+    settings: Arc<ErrorResponseSettings>,
+}
+
+impl ErrorResponseMiddleware {
+    pub fn new(settings: ErrorResponseSettings) -> Self {
+        Self {
+            settings: Arc::new(settings),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl HttpServerMiddleware for ErrorResponseMiddleware {
+    async fn handle_request(
+        &self,
+        ctx: &mut HttpContext,
+    ) -> Option<Result<HttpOkResult, HttpFailResult>> {
+        let languages = if self.settings.localized_messages {
+            get_request_languages(ctx)
+        } else {
+            Vec::new()
+        };
+
+        record_negotiated_error_response(Arc::new(NegotiatedErrorResponse {
+            settings: self.settings.clone(),
+            format: ErrorResponseFormat::detect(ctx, &self.settings),
+            languages,
+        }));
+
+        None
+    }
+}
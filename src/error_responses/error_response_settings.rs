@@ -0,0 +1,217 @@
+use service_sdk::my_http_server::{HttpContext, HttpRequestHeaders};
+
+use crate::ServiceResultStatus;
+
+use super::{get_fallback_chain, MessageCatalogs};
+
+pub const PROBLEM_JSON_CONTENT_TYPE: &str = "application/problem+json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorResponseFormat {
+    // {"status": n}
+    Legacy,
+    // RFC 7807 application/problem+json
+    ProblemJson,
+}
+
+impl ErrorResponseFormat {
+    // Only an explicit application/problem+json media range switches the format.
+    // Everything else (*/*, application/json, no header) keeps the server default.
+    pub fn from_accept_header(accept: &str) -> Option<Self> {
+        for media_range in accept.split(',') {
+            let mut parts = media_range.split(';');
+
+            let media_type = parts.next().unwrap_or_default().trim();
+
+            if !media_type.eq_ignore_ascii_case(PROBLEM_JSON_CONTENT_TYPE) {
+                continue;
+            }
+
+            let rejected = parts.any(|param| match param.trim().split_once('=') {
+                Some((name, value)) => {
+                    name.trim().eq_ignore_ascii_case("q")
+                        && value
+                            .trim()
+                            .parse::<f32>()
+                            .map(|q| q <= 0.0)
+                            .unwrap_or(false)
+                }
+                None => false,
+            });
+
+            if !rejected {
+                return Some(Self::ProblemJson);
+            }
+        }
+
+        None
+    }
+
+    pub fn detect(ctx: &HttpContext, settings: &ErrorResponseSettings) -> Self {
+        if settings.negotiate_by_accept_header {
+            let accept = ctx
+                .request
+                .get_headers()
+                .try_get_case_insensitive_as_str("accept");
+
+            if let Ok(Some(accept)) = accept {
+                if let Some(format) = Self::from_accept_header(accept) {
+                    return format;
+                }
+            }
+        }
+
+        settings.format
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ErrorResponseSettings {
+    pub format: ErrorResponseFormat,
+    pub negotiate_by_accept_header: bool,
+    // Adds `message` resolved from Accept-Language to error bodies
+    pub localized_messages: bool,
+    // "type" member of the problem is this prefix followed by the numeric status
+    pub problem_type_uri_prefix: String,
+    // Messages of the service statuses, looked up before the shared catalogs
+    service_messages: MessageCatalogs,
+}
+
+impl ErrorResponseSettings {
+    pub fn new() -> Self {
+        Self {
+            format: ErrorResponseFormat::Legacy,
+            negotiate_by_accept_header: true,
+            localized_messages: false,
+            problem_type_uri_prefix: String::new(),
+            service_messages: MessageCatalogs::new(),
+        }
+    }
+
+    pub fn with_format(mut self, format: ErrorResponseFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    pub fn with_accept_header_negotiation(mut self, enabled: bool) -> Self {
+        self.negotiate_by_accept_header = enabled;
+        self
+    }
+
+    pub fn with_localized_messages(mut self, enabled: bool) -> Self {
+        self.localized_messages = enabled;
+        self
+    }
+
+    pub fn with_problem_type_uri_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.problem_type_uri_prefix = prefix.into();
+        self
+    }
+
+    // Json object of messages keyed by numeric status. Fails on statuses TStatus does not declare
+    pub fn with_service_messages<TStatus: ServiceResultStatus>(
+        mut self,
+        language: &str,
+        json: &str,
+    ) -> Result<Self, String> {
+        self.service_messages
+            .add_catalog(language, json, |status| {
+                TStatus::get_all()
+                    .iter()
+                    .any(|itm| itm.get_value() == status)
+            })?;
+
+        Ok(self)
+    }
+
+    pub fn get_localized_message(&self, status: i16, languages: &[String]) -> Option<&str> {
+        if !self.localized_messages {
+            return None;
+        }
+
+        let shared_messages = MessageCatalogs::get();
+
+        get_fallback_chain(languages).iter().find_map(|language| {
+            self.service_messages
+                .get_message(status, language)
+                .or_else(|| shared_messages.get_message(status, language))
+        })
+    }
+
+    pub fn get_problem_type_uri(&self, status: i16) -> String {
+        if self.problem_type_uri_prefix.is_empty() {
+            return format!("urn:api-result-status:{}", status);
+        }
+
+        format!("{}{}", self.problem_type_uri_prefix, status)
+    }
+}
+
+impl Default for ErrorResponseSettings {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_accept_header_negotiation() {
+        assert_eq!(
+            ErrorResponseFormat::from_accept_header("application/problem+json"),
+            Some(ErrorResponseFormat::ProblemJson)
+        );
+
+        assert_eq!(
+            ErrorResponseFormat::from_accept_header(
+                "application/json, Application/Problem+JSON;q=0.5"
+            ),
+            Some(ErrorResponseFormat::ProblemJson)
+        );
+
+        assert_eq!(
+            ErrorResponseFormat::from_accept_header("application/problem+json;q=0"),
+            None
+        );
+
+        assert_eq!(ErrorResponseFormat::from_accept_header("*/*"), None);
+        assert_eq!(
+            ErrorResponseFormat::from_accept_header("application/json"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_localized_message() {
+        let languages = vec!["es-ar".to_string()];
+
+        assert_eq!(
+            ErrorResponseSettings::new().get_localized_message(-10, &languages),
+            None
+        );
+
+        assert_eq!(
+            ErrorResponseSettings::new()
+                .with_localized_messages(true)
+                .get_localized_message(-10, &languages),
+            Some("Fondos insuficientes.")
+        );
+    }
+
+    #[test]
+    fn test_problem_type_uri() {
+        assert_eq!(
+            ErrorResponseSettings::new().get_problem_type_uri(-2),
+            "urn:api-result-status:-2"
+        );
+
+        assert_eq!(
+            ErrorResponseSettings::new()
+                .with_problem_type_uri_prefix("https://docs.example.com/errors/")
+                .get_problem_type_uri(-2),
+            "https://docs.example.com/errors/-2"
+        );
+    }
+}
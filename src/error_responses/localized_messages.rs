@@ -0,0 +1,262 @@
+use std::{collections::HashMap, sync::OnceLock};
+
+use service_sdk::my_http_server::{HttpContext, HttpRequestHeaders};
+
+pub const DEFAULT_LANGUAGE: &str = "en";
+
+// Catalogs of ApiResultStatus are embedded at build time. Keys are numeric statuses.
+// Services add catalogs of their own statuses with ErrorResponseSettings::with_service_messages
+const MESSAGE_CATALOGS: &[(&str, &str)] = &[
+    ("en", include_str!("messages/en.json")),
+    ("es", include_str!("messages/es.json")),
+    ("ru", include_str!("messages/ru.json")),
+];
+
+#[derive(Debug, Clone, Default)]
+pub struct MessageCatalogs {
+    catalogs: HashMap<String, HashMap<i16, String>>,
+}
+
+impl MessageCatalogs {
+    pub fn new() -> Self {
+        Self {
+            catalogs: HashMap::new(),
+        }
+    }
+
+    // Shared catalogs of ApiResultStatus
+    pub fn get() -> &'static MessageCatalogs {
+        static INSTANCE: OnceLock<MessageCatalogs> = OnceLock::new();
+        INSTANCE.get_or_init(Self::load)
+    }
+
+    fn load() -> Self {
+        let mut result = Self::new();
+
+        for (language, json) in MESSAGE_CATALOGS {
+            result
+                .add_catalog(language, json, |_| true)
+                .unwrap_or_else(|err| panic!("{}", err));
+        }
+
+        result
+    }
+
+    // Statuses rejected by `is_known` are most likely typos, so the whole catalog is rejected
+    pub fn add_catalog(
+        &mut self,
+        language: &str,
+        json: &str,
+        is_known: impl Fn(i16) -> bool,
+    ) -> Result<(), String> {
+        let messages: HashMap<String, String> = serde_json::from_str(json)
+            .map_err(|err| format!("Invalid message catalog '{}': {}", language, err))?;
+
+        let mut parsed = HashMap::new();
+
+        for (status, message) in messages {
+            let status: i16 = match status.parse() {
+                Ok(status) if is_known(status) => status,
+                _ => {
+                    return Err(format!(
+                        "Unknown status '{}' in catalog '{}'",
+                        status, language
+                    ))
+                }
+            };
+
+            parsed.insert(status, message);
+        }
+
+        self.catalogs
+            .entry(language.to_ascii_lowercase())
+            .or_default()
+            .extend(parsed);
+
+        Ok(())
+    }
+
+    pub fn get_languages(&self) -> impl Iterator<Item = &str> + '_ {
+        self.catalogs.keys().map(|language| language.as_str())
+    }
+
+    pub fn get_message(&self, status: i16, language: &str) -> Option<&str> {
+        self.catalogs
+            .get(language)?
+            .get(&status)
+            .map(|message| message.as_str())
+    }
+
+    // Languages are tried in the order of preference, each one falling back from the most
+    // specific subtag to the primary one (pt-br -> pt), and finally to English
+    pub fn resolve(&self, status: i16, languages: &[String]) -> Option<&str> {
+        get_fallback_chain(languages)
+            .iter()
+            .find_map(|language| self.get_message(status, language))
+    }
+}
+
+pub fn get_fallback_chain(languages: &[String]) -> Vec<String> {
+    let mut result: Vec<String> = Vec::new();
+
+    let mut push = |language: &str| {
+        if !result.iter().any(|itm| itm == language) {
+            result.push(language.to_string());
+        }
+    };
+
+    for language in languages {
+        let mut language = language.as_str();
+
+        loop {
+            push(language);
+
+            match language.rfind('-') {
+                Some(index) => language = &language[..index],
+                None => break,
+            }
+        }
+    }
+
+    push(DEFAULT_LANGUAGE);
+
+    result
+}
+
+// Returns lowercased language tags ordered by quality. Wildcard and q=0 entries are skipped
+pub fn parse_accept_language(header: &str) -> Vec<String> {
+    let mut languages: Vec<(String, f32)> = Vec::new();
+
+    for item in header.split(',') {
+        let mut parts = item.split(';');
+
+        let language = parts.next().unwrap_or_default().trim();
+
+        if language.is_empty() || language == "*" {
+            continue;
+        }
+
+        let quality = parts
+            .filter_map(|param| param.trim().split_once('='))
+            .find(|(name, _)| name.trim().eq_ignore_ascii_case("q"))
+            .map(|(_, value)| value.trim().parse::<f32>().unwrap_or(0.0))
+            .unwrap_or(1.0);
+
+        if quality <= 0.0 {
+            continue;
+        }
+
+        languages.push((language.to_ascii_lowercase(), quality));
+    }
+
+    // Stable sort keeps header order for equal qualities
+    languages.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+    languages
+        .into_iter()
+        .map(|(language, _)| language)
+        .collect()
+}
+
+pub fn get_request_languages(ctx: &HttpContext) -> Vec<String> {
+    match ctx
+        .request
+        .get_headers()
+        .try_get_case_insensitive_as_str("accept-language")
+    {
+        Ok(Some(header)) => parse_accept_language(header),
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ApiResultStatus;
+
+    #[test]
+    fn test_every_status_has_english_message() {
+        let catalogs = MessageCatalogs::get();
+
+        for status in ApiResultStatus::ALL {
+            assert!(
+                catalogs
+                    .get_message(*status as i16, DEFAULT_LANGUAGE)
+                    .is_some(),
+                "{:?}",
+                status
+            );
+        }
+    }
+
+    #[test]
+    fn test_catalogs_contain_only_known_statuses() {
+        let catalogs = MessageCatalogs::get();
+
+        for language in catalogs.get_languages() {
+            for status in catalogs.catalogs.get(language).unwrap().keys() {
+                assert!(
+                    ApiResultStatus::ALL
+                        .iter()
+                        .any(|itm| *itm as i16 == *status),
+                    "{}: {}",
+                    language,
+                    status
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_service_catalog() {
+        let mut catalogs = MessageCatalogs::new();
+
+        catalogs
+            .add_catalog("es", r#"{"-1001": "Documento rechazado."}"#, |status| {
+                status == -1001
+            })
+            .unwrap();
+
+        assert_eq!(
+            catalogs.get_message(-1001, "es"),
+            Some("Documento rechazado.")
+        );
+
+        let result = catalogs.add_catalog("es", r#"{"-1002": "Otro."}"#, |status| status == -1001);
+        assert!(result.is_err());
+        assert_eq!(catalogs.get_message(-1002, "es"), None);
+    }
+
+    #[test]
+    fn test_parse_accept_language() {
+        assert_eq!(
+            parse_accept_language("pt-BR, es;q=0.8, *;q=0.5, ru;q=0.9, de;q=0"),
+            vec!["pt-br", "ru", "es"]
+        );
+
+        assert!(parse_accept_language("").is_empty());
+    }
+
+    #[test]
+    fn test_fallback_chain() {
+        let languages = vec!["zh-hant-tw".to_string(), "es-mx".to_string()];
+
+        assert_eq!(
+            get_fallback_chain(&languages),
+            vec!["zh-hant-tw", "zh-hant", "zh", "es-mx", "es", "en"]
+        );
+    }
+
+    #[test]
+    fn test_resolve() {
+        let catalogs = MessageCatalogs::get();
+
+        let message = catalogs.resolve(-10, &["es-ar".to_string()]);
+        assert_eq!(message, Some("Fondos insuficientes."));
+
+        let message = catalogs.resolve(-10, &["fr".to_string()]);
+        assert_eq!(message, Some("Not enough funds."));
+
+        assert_eq!(catalogs.resolve(-1500, &[]), None);
+    }
+}
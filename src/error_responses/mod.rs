@@ -0,0 +1,13 @@
+mod error_response;
+mod error_response_middleware;
+mod error_response_settings;
+mod localized_messages;
+mod negotiated_error_response;
+mod problem_details;
+
+pub use error_response::*;
+pub use error_response_middleware::*;
+pub use error_response_settings::*;
+pub use localized_messages::*;
+pub use negotiated_error_response::*;
+pub use problem_details::*;
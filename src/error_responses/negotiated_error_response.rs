@@ -0,0 +1,103 @@
+use std::sync::{Arc, OnceLock};
+
+use crate::request_state::{begin_request_state, get_request_state};
+
+use super::{ErrorResponseFormat, ErrorResponseSettings};
+
+// What ErrorResponseMiddleware negotiated for the request served by the current task
+pub struct NegotiatedErrorResponse {
+    pub settings: Arc<ErrorResponseSettings>,
+    pub format: ErrorResponseFormat,
+    // Accept-Language of the request. Empty unless localized messages are enabled
+    pub languages: Vec<String>,
+}
+
+// Starts the state of the request, so values recorded for the previous request served by the
+// same task are dropped
+pub(crate) fn record_negotiated_error_response(negotiated: Arc<NegotiatedErrorResponse>) {
+    begin_request_state(negotiated);
+}
+
+// Default settings are used when ErrorResponseMiddleware is not installed
+pub fn get_negotiated_error_response() -> Arc<NegotiatedErrorResponse> {
+    let negotiated = get_request_state().and_then(|state| state.negotiated_error_response);
+
+    if let Some(negotiated) = negotiated {
+        return negotiated;
+    }
+
+    static DEFAULT: OnceLock<Arc<NegotiatedErrorResponse>> = OnceLock::new();
+
+    DEFAULT
+        .get_or_init(|| {
+            let settings = ErrorResponseSettings::default();
+
+            Arc::new(NegotiatedErrorResponse {
+                format: settings.format,
+                settings: Arc::new(settings),
+                languages: Vec::new(),
+            })
+        })
+        .clone()
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use service_sdk::my_http_server::controllers::AuthErrorFactory;
+    use service_sdk::my_http_server::{HttpFailResult, WebContentType};
+
+    use super::*;
+    use crate::{middlewares::AuthFailResponseFactory, ApiResultStatus};
+
+    #[tokio::test]
+    async fn test_errors_without_context_use_negotiated_format() {
+        tokio::spawn(async {
+            let result: HttpFailResult = ApiResultStatus::TokenIsInvalid.into();
+            assert!(matches!(result.content_type, WebContentType::Json));
+
+            record_negotiated_error_response(Arc::new(NegotiatedErrorResponse {
+                settings: Arc::new(
+                    ErrorResponseSettings::new()
+                        .with_problem_type_uri_prefix("https://docs.example.com/errors/"),
+                ),
+                format: ErrorResponseFormat::ProblemJson,
+                languages: Vec::new(),
+            }));
+
+            let result: HttpFailResult = ApiResultStatus::TokenIsInvalid.into();
+            assert!(matches!(result.content_type, WebContentType::Raw(_)));
+
+            let body: serde_json::Value = serde_json::from_slice(&result.content).unwrap();
+            assert_eq!(body["type"], "https://docs.example.com/errors/-1");
+
+            let result = AuthFailResponseFactory.get_not_authorized("trading".to_string());
+            assert_eq!(result.status_code, 403);
+            assert!(matches!(result.content_type, WebContentType::Raw(_)));
+
+            let body: serde_json::Value = serde_json::from_slice(&result.content).unwrap();
+            assert_eq!(body["data"]["claim"], "trading");
+        })
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_errors_without_context_use_request_languages() {
+        tokio::spawn(async {
+            record_negotiated_error_response(Arc::new(NegotiatedErrorResponse {
+                settings: Arc::new(ErrorResponseSettings::new().with_localized_messages(true)),
+                format: ErrorResponseFormat::Legacy,
+                languages: vec!["es".to_string()],
+            }));
+
+            let result: HttpFailResult = ApiResultStatus::NotEnoughFunds.into();
+
+            let body: serde_json::Value = serde_json::from_slice(&result.content).unwrap();
+            assert_eq!(body["message"], "Fondos insuficientes.");
+        })
+        .await
+        .unwrap();
+    }
+}
@@ -0,0 +1,69 @@
+use serde::Serialize;
+use service_sdk::my_http_server::{HttpFailResult, WebContentType};
+
+use crate::ResultStatus;
+
+use super::{get_negotiated_error_response, PROBLEM_JSON_CONTENT_TYPE};
+
+// RFC 7807 body. Our numeric status is kept as the `resultStatus` extension member
+#[derive(Serialize, Debug)]
+pub struct ProblemDetails {
+    #[serde(rename = "type")]
+    pub type_uri: String,
+    pub title: String,
+    pub status: u16,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    #[serde(rename = "resultStatus")]
+    pub result_status: i16,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<serde_json::Value>,
+    #[serde(skip)]
+    pub write_to_telemetry: bool,
+}
+
+impl ProblemDetails {
+    pub fn new(status: &impl ResultStatus) -> Self {
+        Self {
+            type_uri: get_negotiated_error_response()
+                .settings
+                .get_problem_type_uri(status.get_value()),
+            title: status.get_description().to_string(),
+            status: status.get_status_code(),
+            detail: None,
+            result_status: status.get_value(),
+            message: None,
+            data: None,
+            write_to_telemetry: status.write_to_telemetry(),
+        }
+    }
+
+    pub fn with_detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+
+    pub fn with_message(mut self, message: impl Into<String>) -> Self {
+        self.message = Some(message.into());
+        self
+    }
+
+    pub fn with_data(mut self, data: &impl Serialize) -> Self {
+        self.data = serde_json::to_value(data).ok();
+        self
+    }
+}
+
+impl Into<HttpFailResult> for ProblemDetails {
+    fn into(self) -> HttpFailResult {
+        HttpFailResult::new(
+            WebContentType::Raw(PROBLEM_JSON_CONTENT_TYPE.to_string()),
+            self.status,
+            serde_json::to_vec(&self).unwrap(),
+            self.write_to_telemetry,
+            self.write_to_telemetry,
+        )
+    }
+}
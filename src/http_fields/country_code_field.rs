@@ -3,24 +3,38 @@ use service_sdk::rust_extensions::{self, StrOrString};
 
 service_sdk::macros::use_my_http_server!();
 
+use super::{FieldValidationError, ValidatedHttpField, INVALID_COUNTRY_CODE};
+
 #[http_input_field]
 pub struct CountryCodeHttpField(String);
 
 fn process_value(src: &str) -> Result<StrOrString, HttpFailResult> {
+    validate_value(src).map_err(|err| err.into())
+}
+
+fn validate_value(src: &str) -> Result<StrOrString, FieldValidationError> {
     let value = src.trim().to_uppercase();
 
     let parsed = CountryCode::parse(value.as_str());
 
     if parsed.is_err() {
-        return Err(HttpFailResult::as_validation_error(format!(
-            "Country code {} is not valid",
-            src
-        )));
+        return Err(FieldValidationError::new(
+            INVALID_COUNTRY_CODE,
+            format!("Country code {} is not valid", src),
+        )
+        .with_param("value", src));
     }
 
     Ok(StrOrString::create_as_string(value))
 }
 
+impl ValidatedHttpField for CountryCodeHttpField {
+    fn validate(src: &str) -> Result<Self, FieldValidationError> {
+        let value = validate_value(src)?;
+        Ok(Self(value.as_str().to_string()))
+    }
+}
+
 impl Into<CountryCode> for CountryCodeHttpField {
     fn into(self) -> CountryCode {
         CountryCode::parse(self.0.as_str()).unwrap()
@@ -2,17 +2,31 @@ service_sdk::macros::use_my_http_server!();
 use email_address::EmailAddress;
 use service_sdk::rust_extensions::{self, StrOrString};
 
+use super::{FieldValidationError, ValidatedHttpField, INVALID_EMAIL};
+
 #[http_input_field]
 pub struct EmailHttpField(String);
 
 fn process_value(src: &str) -> Result<StrOrString, HttpFailResult> {
+    validate_value(src).map_err(|err| err.into())
+}
+
+fn validate_value(src: &str) -> Result<StrOrString, FieldValidationError> {
     let email = src.trim().to_lowercase();
 
     if !EmailAddress::is_valid(&email) {
-        return Err(HttpFailResult::as_validation_error(
-            "Email is not valid".to_string(),
+        return Err(FieldValidationError::new(
+            INVALID_EMAIL,
+            "Email is not valid",
         ));
     }
 
     Ok(StrOrString::create_as_string(email))
 }
+
+impl ValidatedHttpField for EmailHttpField {
+    fn validate(src: &str) -> Result<Self, FieldValidationError> {
+        let value = validate_value(src)?;
+        Ok(Self(value.as_str().to_string()))
+    }
+}
@@ -0,0 +1,78 @@
+use std::{collections::HashMap, sync::OnceLock};
+
+use crate::error_responses::get_fallback_chain;
+
+// Messages keyed by validation code. Params of the error are substituted into {name}
+// placeholders. English is the fallback for languages which have no catalog
+const FIELD_MESSAGE_CATALOGS: &[(&str, &str)] = &[
+    ("en", include_str!("messages/en.json")),
+    ("es", include_str!("messages/es.json")),
+    ("ru", include_str!("messages/ru.json")),
+];
+
+fn get_catalogs() -> &'static HashMap<&'static str, HashMap<String, String>> {
+    static INSTANCE: OnceLock<HashMap<&'static str, HashMap<String, String>>> = OnceLock::new();
+
+    INSTANCE.get_or_init(|| {
+        FIELD_MESSAGE_CATALOGS
+            .iter()
+            .map(|(language, json)| {
+                let messages = serde_json::from_str(json).unwrap_or_else(|err| {
+                    panic!("Invalid field message catalog '{}': {}", language, err)
+                });
+                (*language, messages)
+            })
+            .collect()
+    })
+}
+
+pub fn get_field_message_template(code: &str, languages: &[String]) -> Option<&'static str> {
+    let catalogs = get_catalogs();
+
+    get_fallback_chain(languages).iter().find_map(|language| {
+        catalogs
+            .get(language.as_str())?
+            .get(code)
+            .map(|message| message.as_str())
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::http_fields::{DIGIT_REQUIRED, INVALID_COUNTRY_CODE, INVALID_EMAIL, TOO_SHORT};
+
+    #[test]
+    fn test_every_code_has_message() {
+        for (language, messages) in get_catalogs() {
+            for code in [
+                INVALID_EMAIL,
+                TOO_SHORT,
+                DIGIT_REQUIRED,
+                INVALID_COUNTRY_CODE,
+            ] {
+                assert!(
+                    messages.contains_key(code),
+                    "No message for '{}' in '{}'",
+                    code,
+                    language
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_english_is_the_fallback() {
+        let languages = vec!["fr".to_string()];
+        assert_eq!(
+            get_field_message_template(TOO_SHORT, &languages),
+            Some("Must be at least {minLength} characters long.")
+        );
+
+        let languages = vec!["es-mx".to_string()];
+        assert_eq!(
+            get_field_message_template(TOO_SHORT, &languages),
+            Some("Debe tener al menos {minLength} caracteres.")
+        );
+    }
+}
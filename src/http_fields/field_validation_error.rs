@@ -0,0 +1,109 @@
+use serde::Serialize;
+use service_sdk::my_http_server;
+use service_sdk::my_http_server::macros::MyHttpObjectStructure;
+use service_sdk::my_http_server::HttpFailResult;
+
+use crate::error_responses::get_negotiated_error_response;
+
+use super::get_field_message_template;
+
+pub const INVALID_EMAIL: &str = "invalid_email";
+pub const TOO_SHORT: &str = "too_short";
+pub const DIGIT_REQUIRED: &str = "digit_required";
+pub const INVALID_COUNTRY_CODE: &str = "invalid_country_code";
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq, MyHttpObjectStructure)]
+pub struct FieldValidationParam {
+    pub name: String,
+    pub value: String,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq, MyHttpObjectStructure)]
+pub struct FieldValidationError {
+    // Path of the field in the request, e.g. "address.country". Empty until the error is
+    // attached to a field by ValidationErrors
+    pub field: String,
+    pub code: String,
+    pub message: String,
+    pub params: Vec<FieldValidationParam>,
+}
+
+impl FieldValidationError {
+    pub fn new(code: &str, message: impl Into<String>) -> Self {
+        Self {
+            field: String::new(),
+            code: code.to_string(),
+            message: message.into(),
+            params: Vec::new(),
+        }
+    }
+
+    pub fn with_field(mut self, field: impl Into<String>) -> Self {
+        self.field = field.into();
+        self
+    }
+
+    pub fn with_param(mut self, name: &str, value: impl ToString) -> Self {
+        self.params.push(FieldValidationParam {
+            name: name.to_string(),
+            value: value.to_string(),
+        });
+        self
+    }
+
+    pub fn get_param(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|param| param.name == name)
+            .map(|param| param.value.as_str())
+    }
+
+    // Message is left as is when no catalog (English included) has the code
+    pub fn localize(mut self, languages: &[String]) -> Self {
+        let Some(template) = get_field_message_template(&self.code, languages) else {
+            return self;
+        };
+
+        let mut message = template.to_string();
+
+        for param in &self.params {
+            message = message.replace(&format!("{{{}}}", param.name), &param.value);
+        }
+
+        self.message = message;
+        self
+    }
+}
+
+// Keeps the legacy single string validation error for fields parsed by #[http_input_field].
+// The message is in the language negotiated by ErrorResponseMiddleware
+impl Into<HttpFailResult> for FieldValidationError {
+    fn into(self) -> HttpFailResult {
+        let negotiated = get_negotiated_error_response();
+
+        let error = if negotiated.settings.localized_messages {
+            self.localize(&negotiated.languages)
+        } else {
+            self
+        };
+
+        HttpFailResult::as_validation_error(error.message)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_localized_message() {
+        let error = FieldValidationError::new(TOO_SHORT, "Password must have at least 6 chars")
+            .with_param("minLength", 6)
+            .localize(&["ru".to_string()]);
+
+        assert_eq!(error.message, "Должно быть не менее 6 символов.");
+
+        let error = error.localize(&["fr".to_string()]);
+        assert_eq!(error.message, "Must be at least 6 characters long.");
+    }
+}
@@ -4,3 +4,9 @@ mod country_code_field;
 pub use country_code_field::*;
 mod password_field;
 pub use password_field::*;
+mod field_messages;
+pub use field_messages::*;
+mod field_validation_error;
+pub use field_validation_error::*;
+mod validation_errors;
+pub use validation_errors::*;
@@ -2,16 +2,24 @@ use service_sdk::rust_extensions::{self, StrOrString};
 
 service_sdk::macros::use_my_http_server!();
 
+use super::{FieldValidationError, ValidatedHttpField, DIGIT_REQUIRED, TOO_SHORT};
+
 const PASSWORD_LENGTH: usize = 6;
 
 #[http_input_field]
 pub struct PasswordHttpField(String);
 
 fn process_value(src: &str) -> Result<StrOrString, HttpFailResult> {
+    validate_value(src).map_err(|err| err.into())
+}
+
+fn validate_value(src: &str) -> Result<StrOrString, FieldValidationError> {
     if src.len() < PASSWORD_LENGTH {
-        return Err(HttpFailResult::as_validation_error(format!(
-            "Password must have at least {PASSWORD_LENGTH} chars",
-        )));
+        return Err(FieldValidationError::new(
+            TOO_SHORT,
+            format!("Password must have at least {PASSWORD_LENGTH} chars"),
+        )
+        .with_param("minLength", PASSWORD_LENGTH));
     }
 
     let mut digits = 0;
@@ -23,10 +31,46 @@ fn process_value(src: &str) -> Result<StrOrString, HttpFailResult> {
     }
 
     if digits == 0 {
-        return Err(HttpFailResult::as_validation_error(
-            "Password must have at least one digit".to_string(),
-        ));
+        return Err(FieldValidationError::new(
+            DIGIT_REQUIRED,
+            "Password must have at least one digit",
+        )
+        .with_param("minDigits", 1));
     }
 
     Ok(StrOrString::create_as_str(src))
 }
+
+impl ValidatedHttpField for PasswordHttpField {
+    fn validate(src: &str) -> Result<Self, FieldValidationError> {
+        let value = validate_value(src)?;
+        Ok(Self(value.as_str().to_string()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_too_short_password() {
+        let err = PasswordHttpField::validate("ab1").err().unwrap();
+
+        assert_eq!(err.code, TOO_SHORT);
+        assert_eq!(err.get_param("minLength"), Some("6"));
+    }
+
+    #[test]
+    fn test_password_without_digits() {
+        let err = PasswordHttpField::validate("abcdefg").err().unwrap();
+
+        assert_eq!(err.code, DIGIT_REQUIRED);
+    }
+
+    #[test]
+    fn test_legacy_validation_error() {
+        let processed = PasswordHttpField::new("ab1");
+
+        assert_eq!(processed.unwrap_err().status_code, 400);
+    }
+}
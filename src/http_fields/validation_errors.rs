@@ -0,0 +1,184 @@
+use serde::Serialize;
+use service_sdk::my_http_server;
+use service_sdk::my_http_server::macros::MyHttpObjectStructure;
+use service_sdk::my_http_server::{HttpContext, HttpFailResult};
+
+use crate::error_responses::{
+    get_negotiated_error_response, get_request_languages, ErrorResponse, ErrorResponseSettings,
+};
+use crate::{ApiHttpResultWithData, ApiResultStatus};
+
+use super::FieldValidationError;
+
+// Implemented by http fields which can report structured validation errors
+pub trait ValidatedHttpField: Sized {
+    fn validate(src: &str) -> Result<Self, FieldValidationError>;
+}
+
+#[derive(Serialize, Debug, MyHttpObjectStructure)]
+pub struct ValidationErrorsHttpModel {
+    pub errors: Vec<FieldValidationError>,
+}
+
+// Collects errors of all the fields of a request so the client can highlight all of them at once.
+// Fields declared with #[http_input_field] are parsed by my_http_server before the action is
+// called and report only the first invalid field, as a single string error
+#[derive(Debug, Default)]
+pub struct ValidationErrors {
+    errors: Vec<FieldValidationError>,
+}
+
+impl ValidationErrors {
+    pub fn new() -> Self {
+        Self { errors: Vec::new() }
+    }
+
+    pub fn validate<TField: ValidatedHttpField>(
+        &mut self,
+        field: &str,
+        src: &str,
+    ) -> Option<TField> {
+        match TField::validate(src) {
+            Ok(value) => Some(value),
+            Err(err) => {
+                self.errors.push(err.with_field(field));
+                None
+            }
+        }
+    }
+
+    pub fn add(&mut self, error: FieldValidationError) {
+        self.errors.push(error);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    pub fn get_errors(&self) -> &[FieldValidationError] {
+        self.errors.as_slice()
+    }
+
+    pub fn into_api_result(self) -> ApiHttpResultWithData<ValidationErrorsHttpModel> {
+        ApiHttpResultWithData {
+            status: ApiResultStatus::PersonalDataNotValid,
+            data: Some(ValidationErrorsHttpModel {
+                errors: self.errors,
+            }),
+        }
+    }
+
+    fn localize(self, settings: &ErrorResponseSettings, languages: &[String]) -> Self {
+        if !settings.localized_messages {
+            return self;
+        }
+
+        Self {
+            errors: self
+                .errors
+                .into_iter()
+                .map(|err| err.localize(languages))
+                .collect(),
+        }
+    }
+
+    // Format and language negotiated by ErrorResponseMiddleware for the current request
+    pub fn into_result(self) -> Result<(), HttpFailResult> {
+        if self.errors.is_empty() {
+            return Ok(());
+        }
+
+        let negotiated = get_negotiated_error_response();
+
+        Err(self
+            .localize(&negotiated.settings, &negotiated.languages)
+            .into_api_result()
+            .into_fail_result())
+    }
+
+    // Format and language are negotiated by Accept and Accept-Language headers
+    pub fn into_result_for(self, ctx: &HttpContext) -> Result<(), HttpFailResult> {
+        if self.errors.is_empty() {
+            return Ok(());
+        }
+
+        let negotiated = get_negotiated_error_response();
+
+        Err(self
+            .localize(&negotiated.settings, &get_request_languages(ctx))
+            .into_api_result()
+            .into_fail_result_for(ctx))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::error_responses::{
+        record_negotiated_error_response, ErrorResponseFormat, NegotiatedErrorResponse,
+    };
+    use crate::http_fields::{
+        CountryCodeHttpField, EmailHttpField, PasswordHttpField, INVALID_EMAIL, TOO_SHORT,
+    };
+
+    #[test]
+    fn test_errors_are_aggregated() {
+        let mut errors = ValidationErrors::new();
+
+        let email: Option<EmailHttpField> = errors.validate("email", "not-an-email");
+        let password: Option<PasswordHttpField> = errors.validate("password", "abc");
+        let country: Option<CountryCodeHttpField> = errors.validate("address.country", "usa");
+
+        assert!(email.is_none());
+        assert!(password.is_none());
+        assert!(country.is_some());
+
+        let result = errors.get_errors();
+
+        assert_eq!(result.len(), 2);
+
+        assert_eq!(result[0].field, "email");
+        assert_eq!(result[0].code, INVALID_EMAIL);
+
+        assert_eq!(result[1].field, "password");
+        assert_eq!(result[1].code, TOO_SHORT);
+        assert_eq!(result[1].get_param("minLength"), Some("6"));
+    }
+
+    #[test]
+    fn test_no_errors() {
+        let mut errors = ValidationErrors::new();
+
+        let email: Option<EmailHttpField> = errors.validate("email", "trader@example.com");
+
+        assert!(email.is_some());
+        assert!(errors.into_result().is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_errors_are_localized() {
+        tokio::spawn(async {
+            record_negotiated_error_response(Arc::new(NegotiatedErrorResponse {
+                settings: Arc::new(ErrorResponseSettings::new().with_localized_messages(true)),
+                format: ErrorResponseFormat::Legacy,
+                languages: vec!["es".to_string()],
+            }));
+
+            let mut errors = ValidationErrors::new();
+            let email: Option<EmailHttpField> = errors.validate("email", "not-an-email");
+            assert!(email.is_none());
+
+            let result = errors.into_result().unwrap_err();
+
+            let body: serde_json::Value = serde_json::from_slice(&result.content).unwrap();
+            assert_eq!(
+                body["data"]["errors"][0]["message"],
+                "El correo electrónico no es válido."
+            );
+        })
+        .await
+        .unwrap();
+    }
+}
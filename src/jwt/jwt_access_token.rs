@@ -0,0 +1,54 @@
+use serde::{Deserialize, Serialize};
+use service_sdk::rust_extensions::date_time::DateTimeAsMicroseconds;
+
+use crate::session_store::SessionDenylist;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct JwtSessionClaim {
+    pub name: String,
+    // Unix seconds
+    pub exp: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ip: Option<String>,
+}
+
+// RFC 8693 actor. Set when a support agent acts as the trader
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct JwtActor {
+    pub sub: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct JwtAccessToken {
+    // Trader id
+    pub sub: String,
+    pub exp: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nbf: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub aud: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ip: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub claims: Option<Vec<JwtSessionClaim>>,
+    // Synchronizer CSRF token for web clients which keep the JWT in a cookie
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub csrf: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub act: Option<JwtActor>,
+    // Token id. Revoked by adding it to SessionDenylist
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub jti: Option<String>,
+    // Session the token was issued for. Revoking the session revokes the token too
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sid: Option<String>,
+}
+
+impl JwtAccessToken {
+    pub fn is_revoked(&self, denylist: &SessionDenylist, now: DateTimeAsMicroseconds) -> bool {
+        [self.jti.as_deref(), self.sid.as_deref()]
+            .into_iter()
+            .flatten()
+            .any(|id| denylist.is_revoked(id, now))
+    }
+}
@@ -0,0 +1,139 @@
+use my_http_server::*;
+use service_sdk::my_http_server;
+use service_sdk::rust_extensions::date_time::DateTimeAsMicroseconds;
+use std::sync::Arc;
+
+use crate::middlewares::{
+    clear_auth_fail_reason, continue_unauthenticated, AuthFailReason, ClaimsResolver,
+    CsrfProtection, ImpersonatedRequest, ImpersonationPolicy, IpBindingPolicy, SessionTokenSources,
+};
+use crate::session_store::SessionDenylist;
+
+use super::{JwtRequestCredentials, JwtVerifier, JwtVerifyError};
+
+pub struct JwtAuthMiddleware {
+    verifier: JwtVerifier,
+    ip_binding_policy: IpBindingPolicy,
+    token_sources: SessionTokenSources,
+    csrf_protection: Option<CsrfProtection>,
+    claims_resolver: Option<Arc<ClaimsResolver>>,
+    impersonation_policy: ImpersonationPolicy,
+    denylist: Option<Arc<SessionDenylist>>,
+}
+
+impl JwtAuthMiddleware {
+    pub fn new(verifier: JwtVerifier) -> Self {
+        Self {
+            verifier,
+            ip_binding_policy: IpBindingPolicy::default(),
+            token_sources: SessionTokenSources::default(),
+            csrf_protection: None,
+            claims_resolver: None,
+            impersonation_policy: ImpersonationPolicy::default(),
+            denylist: None,
+        }
+    }
+
+    pub fn with_ip_binding_policy(mut self, ip_binding_policy: IpBindingPolicy) -> Self {
+        self.ip_binding_policy = ip_binding_policy;
+        self
+    }
+
+    pub fn with_token_sources(mut self, token_sources: SessionTokenSources) -> Self {
+        self.token_sources = token_sources;
+        self
+    }
+
+    // Synchronizer token mode compares X-CSRF-Token with the `csrf` claim of the token
+    pub fn with_csrf_protection(mut self, csrf_protection: CsrfProtection) -> Self {
+        self.csrf_protection = Some(csrf_protection);
+        self
+    }
+
+    pub fn with_claims_resolver(mut self, claims_resolver: Arc<ClaimsResolver>) -> Self {
+        self.claims_resolver = Some(claims_resolver);
+        self
+    }
+
+    pub fn with_impersonation_policy(mut self, impersonation_policy: ImpersonationPolicy) -> Self {
+        self.impersonation_policy = impersonation_policy;
+        self
+    }
+
+    // Same denylist as of AuthMiddleware: tokens whose jti or sid was revoked are rejected
+    pub fn with_denylist(mut self, denylist: Arc<SessionDenylist>) -> Self {
+        self.denylist = Some(denylist);
+        self
+    }
+}
+
+#[async_trait::async_trait]
+impl HttpServerMiddleware for JwtAuthMiddleware {
+    async fn handle_request(
+        &self,
+        ctx: &mut HttpContext,
+    ) -> Option<Result<HttpOkResult, HttpFailResult>> {
+        clear_auth_fail_reason();
+
+        let session_token = match self.token_sources.get_session_token(ctx) {
+            Ok(session_token) => session_token?,
+            Err(_) => return continue_unauthenticated(AuthFailReason::TokenIsInvalid),
+        };
+
+        let access_token = match self.verifier.verify(session_token.value.as_str()) {
+            Ok(access_token) => access_token,
+            Err(JwtVerifyError::Expired) => {
+                return continue_unauthenticated(AuthFailReason::TokenExpired)
+            }
+            Err(JwtVerifyError::Invalid(_)) => {
+                return continue_unauthenticated(AuthFailReason::TokenIsInvalid)
+            }
+        };
+
+        if let Some(denylist) = &self.denylist {
+            if access_token.is_revoked(denylist, DateTimeAsMicroseconds::now()) {
+                return continue_unauthenticated(AuthFailReason::TokenIsInvalid);
+            }
+        }
+
+        if session_token.from_cookie {
+            if let Some(csrf_protection) = &self.csrf_protection {
+                if let Err(reason) =
+                    csrf_protection.validate_token(ctx, access_token.csrf.as_deref())
+                {
+                    return continue_unauthenticated(reason);
+                }
+            }
+        }
+
+        let request_ip = ctx.request.get_ip();
+        let request_ip = request_ip.get_real_ip();
+
+        let mut credentials = JwtRequestCredentials::new_with_claims_resolver(
+            access_token,
+            request_ip,
+            &self.ip_binding_policy,
+            self.claims_resolver.as_deref(),
+        );
+
+        credentials.apply_impersonation_policy(&self.impersonation_policy);
+
+        if let Some(impersonator_id) = credentials.get_impersonator_id() {
+            if let Some(audit_hook) = &self.impersonation_policy.audit_hook {
+                audit_hook
+                    .on_impersonated_request(ImpersonatedRequest {
+                        trader_id: &credentials.access_token.sub,
+                        impersonator_id,
+                        method: ctx.request.method.as_str(),
+                        path: ctx.request.http_path.as_str(),
+                        ip: request_ip,
+                    })
+                    .await;
+            }
+        }
+
+        ctx.credentials = Some(Box::new(credentials));
+
+        None
+    }
+}
@@ -0,0 +1,102 @@
+use std::{collections::HashMap, sync::RwLock};
+
+use jsonwebtoken::{Algorithm, DecodingKey};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JwtAlgorithm {
+    HS256,
+    RS256,
+    EdDSA,
+}
+
+impl JwtAlgorithm {
+    pub fn to_algorithm(self) -> Algorithm {
+        match self {
+            JwtAlgorithm::HS256 => Algorithm::HS256,
+            JwtAlgorithm::RS256 => Algorithm::RS256,
+            JwtAlgorithm::EdDSA => Algorithm::EdDSA,
+        }
+    }
+}
+
+pub struct JwtKey {
+    pub algorithm: JwtAlgorithm,
+    pub decoding_key: DecodingKey,
+}
+
+impl JwtKey {
+    // HS256 - file contains the shared secret, RS256 and EdDSA - file contains public key in PEM format
+    pub fn load_from_file(algorithm: JwtAlgorithm, path: &str) -> Result<Self, String> {
+        let content = std::fs::read(path)
+            .map_err(|err| format!("Can not read jwt key file {}. Err: {}", path, err))?;
+
+        Self::from_bytes(algorithm, &content)
+            .map_err(|err| format!("Can not load jwt key file {}. Err: {}", path, err))
+    }
+
+    pub fn from_bytes(algorithm: JwtAlgorithm, content: &[u8]) -> Result<Self, String> {
+        let decoding_key = match algorithm {
+            JwtAlgorithm::HS256 => DecodingKey::from_secret(content.trim_ascii()),
+            JwtAlgorithm::RS256 => {
+                DecodingKey::from_rsa_pem(content).map_err(|err| format!("{:?}", err))?
+            }
+            JwtAlgorithm::EdDSA => {
+                DecodingKey::from_ed_pem(content).map_err(|err| format!("{:?}", err))?
+            }
+        };
+
+        Ok(Self {
+            algorithm,
+            decoding_key,
+        })
+    }
+}
+
+// Keys are selected by `kid` header. Several keys can be active at once to rotate them without downtime
+pub struct JwtKeys {
+    keys: RwLock<HashMap<String, std::sync::Arc<JwtKey>>>,
+    default_kid: RwLock<Option<String>>,
+}
+
+impl JwtKeys {
+    pub fn new() -> Self {
+        Self {
+            keys: RwLock::new(HashMap::new()),
+            default_kid: RwLock::new(None),
+        }
+    }
+
+    pub fn add_key(&self, kid: impl Into<String>, key: JwtKey) {
+        let mut keys = self.keys.write().unwrap();
+        keys.insert(kid.into(), std::sync::Arc::new(key));
+    }
+
+    pub fn remove_key(&self, kid: &str) {
+        let mut keys = self.keys.write().unwrap();
+        keys.remove(kid);
+    }
+
+    // Key used for tokens without `kid` header
+    pub fn set_default_kid(&self, kid: Option<String>) {
+        let mut default_kid = self.default_kid.write().unwrap();
+        *default_kid = kid;
+    }
+
+    pub fn get_key(&self, kid: Option<&str>) -> Option<std::sync::Arc<JwtKey>> {
+        let keys = self.keys.read().unwrap();
+
+        match kid {
+            Some(kid) => keys.get(kid).cloned(),
+            None => {
+                let default_kid = self.default_kid.read().unwrap();
+                keys.get(default_kid.as_ref()?).cloned()
+            }
+        }
+    }
+}
+
+impl Default for JwtKeys {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,157 @@
+use service_sdk::my_http_server;
+use service_sdk::my_http_server::{RequestClaim, RequestCredentials};
+use service_sdk::rust_extensions::date_time::DateTimeAsMicroseconds;
+
+use crate::middlewares::{ClaimsResolver, ImpersonationPolicy, IpBindingPolicy, ResolvedClaim};
+
+use super::JwtAccessToken;
+
+struct CredentialsClaim {
+    name: String,
+    expires: DateTimeAsMicroseconds,
+    allowed_ips: Option<Vec<String>>,
+}
+
+pub struct JwtRequestCredentials {
+    pub access_token: JwtAccessToken,
+    // None when the token has no claims
+    claims: Option<Vec<CredentialsClaim>>,
+}
+
+impl JwtRequestCredentials {
+    pub fn new(
+        access_token: JwtAccessToken,
+        request_ip: &str,
+        ip_binding_policy: &IpBindingPolicy,
+    ) -> Self {
+        Self::new_with_claims_resolver(access_token, request_ip, ip_binding_policy, None)
+    }
+
+    pub fn new_with_claims_resolver(
+        access_token: JwtAccessToken,
+        request_ip: &str,
+        ip_binding_policy: &IpBindingPolicy,
+        claims_resolver: Option<&ClaimsResolver>,
+    ) -> Self {
+        let Some(jwt_claims) = access_token.claims.as_ref() else {
+            return Self {
+                access_token,
+                claims: None,
+            };
+        };
+
+        let resolved_claims = match claims_resolver {
+            Some(claims_resolver) => claims_resolver.resolve(
+                jwt_claims
+                    .iter()
+                    .map(|claim| (claim.name.as_str(), claim.exp, claim.ip.as_deref())),
+            ),
+            None => jwt_claims
+                .iter()
+                .map(|claim| ResolvedClaim {
+                    name: claim.name.clone(),
+                    expires: claim.exp,
+                    ip: claim.ip.clone(),
+                })
+                .collect(),
+        };
+
+        // A claim whose expiration (unix seconds) does not fit into microseconds is not granted
+        let claims = resolved_claims
+            .into_iter()
+            .filter_map(|claim| {
+                let expires = claim.expires.checked_mul(1_000_000)?;
+
+                Some(CredentialsClaim {
+                    allowed_ips: ip_binding_policy.get_allowed_ips(
+                        claim.ip.as_deref(),
+                        access_token.ip.as_deref(),
+                        request_ip,
+                    ),
+                    name: claim.name,
+                    expires: DateTimeAsMicroseconds::new(expires),
+                })
+            })
+            .collect();
+
+        Self {
+            access_token,
+            claims: Some(claims),
+        }
+    }
+
+    pub fn get_impersonator_id(&self) -> Option<&str> {
+        self.access_token.act.as_ref().map(|act| act.sub.as_str())
+    }
+
+    // Does nothing for tokens which are not impersonated
+    pub fn apply_impersonation_policy(&mut self, impersonation_policy: &ImpersonationPolicy) {
+        if self.access_token.act.is_none() {
+            return;
+        }
+
+        if let Some(claims) = self.claims.as_mut() {
+            claims.retain(|claim| !impersonation_policy.is_claim_denied(&claim.name, &[]));
+        }
+    }
+}
+
+impl RequestCredentials for JwtRequestCredentials {
+    fn get_id(&self) -> &str {
+        &self.access_token.sub
+    }
+
+    fn get_claims<'s>(&'s self) -> Option<Vec<my_http_server::RequestClaim<'s>>> {
+        let claims = self.claims.as_ref()?;
+
+        let mut result = Vec::with_capacity(claims.len());
+
+        for claim in claims {
+            result.push(RequestClaim {
+                id: &claim.name,
+                expires: claim.expires,
+                allowed_ips: claim.allowed_ips.as_ref(),
+            });
+        }
+
+        Some(result)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::jwt::JwtSessionClaim;
+
+    #[test]
+    fn test_claim_with_overflowing_expiration_is_not_granted() {
+        let claim = |name: &str, exp: i64| JwtSessionClaim {
+            name: name.to_string(),
+            exp,
+            ip: None,
+        };
+
+        let access_token = JwtAccessToken {
+            sub: "trader".to_string(),
+            exp: 0,
+            nbf: None,
+            aud: None,
+            ip: None,
+            claims: Some(vec![
+                claim("trading", 2_000_000_000),
+                claim("withdraw", i64::MAX),
+            ]),
+            csrf: None,
+            act: None,
+            jti: None,
+            sid: None,
+        };
+
+        let credentials =
+            JwtRequestCredentials::new(access_token, "10.0.0.1", &IpBindingPolicy::default());
+
+        let claims = credentials.get_claims().unwrap();
+        assert_eq!(claims.len(), 1);
+        assert_eq!(claims[0].id, "trading");
+    }
+}
@@ -0,0 +1,208 @@
+use std::sync::Arc;
+
+use jsonwebtoken::{errors::ErrorKind, Validation};
+
+use super::{JwtAccessToken, JwtKeys};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JwtVerifyError {
+    Expired,
+    Invalid(String),
+}
+
+pub struct JwtVerifier {
+    keys: Arc<JwtKeys>,
+    audience: Vec<String>,
+    leeway_seconds: u64,
+}
+
+impl JwtVerifier {
+    pub fn new(keys: Arc<JwtKeys>) -> Self {
+        Self {
+            keys,
+            audience: Vec::new(),
+            leeway_seconds: 30,
+        }
+    }
+
+    pub fn with_audience(mut self, audience: impl Into<String>) -> Self {
+        self.audience.push(audience.into());
+        self
+    }
+
+    pub fn with_leeway_seconds(mut self, leeway_seconds: u64) -> Self {
+        self.leeway_seconds = leeway_seconds;
+        self
+    }
+
+    pub fn get_keys(&self) -> &Arc<JwtKeys> {
+        &self.keys
+    }
+
+    pub fn verify(&self, token: &str) -> Result<JwtAccessToken, JwtVerifyError> {
+        let header = jsonwebtoken::decode_header(token)
+            .map_err(|err| JwtVerifyError::Invalid(format!("{:?}", err)))?;
+
+        let key = self
+            .keys
+            .get_key(header.kid.as_deref())
+            .ok_or_else(|| JwtVerifyError::Invalid(format!("Unknown kid {:?}", header.kid)))?;
+
+        // Algorithm is taken from our key, never from the token header
+        let mut validation = Validation::new(key.algorithm.to_algorithm());
+        validation.leeway = self.leeway_seconds;
+        validation.validate_nbf = true;
+
+        if self.audience.is_empty() {
+            validation.validate_aud = false;
+        } else {
+            validation.set_audience(&self.audience);
+        }
+
+        match jsonwebtoken::decode::<JwtAccessToken>(token, &key.decoding_key, &validation) {
+            Ok(token_data) => Ok(token_data.claims),
+            Err(err) => match err.kind() {
+                ErrorKind::ExpiredSignature => Err(JwtVerifyError::Expired),
+                _ => Err(JwtVerifyError::Invalid(format!("{:?}", err))),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use jsonwebtoken::{EncodingKey, Header};
+
+    use super::*;
+    use crate::jwt::{JwtAlgorithm, JwtKey};
+    use crate::session_store::SessionDenylist;
+    use service_sdk::rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    const SECRET: &[u8] = b"test-secret";
+
+    fn now() -> i64 {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64
+    }
+
+    fn create_verifier() -> JwtVerifier {
+        let keys = JwtKeys::new();
+        keys.add_key(
+            "k1",
+            JwtKey::from_bytes(JwtAlgorithm::HS256, SECRET).unwrap(),
+        );
+        JwtVerifier::new(Arc::new(keys)).with_audience("wallet")
+    }
+
+    fn encode(kid: Option<&str>, token: &JwtAccessToken) -> String {
+        let mut header = Header::new(jsonwebtoken::Algorithm::HS256);
+        header.kid = kid.map(|kid| kid.to_string());
+        jsonwebtoken::encode(&header, token, &EncodingKey::from_secret(SECRET)).unwrap()
+    }
+
+    fn access_token(exp: i64) -> JwtAccessToken {
+        JwtAccessToken {
+            sub: "trader-1".to_string(),
+            exp,
+            nbf: None,
+            aud: Some("wallet".to_string()),
+            ip: None,
+            claims: None,
+            csrf: None,
+            act: None,
+            jti: None,
+            sid: None,
+        }
+    }
+
+    #[test]
+    fn test_valid_token() {
+        let verifier = create_verifier();
+        let token = encode(Some("k1"), &access_token(now() + 60));
+
+        let result = verifier.verify(&token).unwrap();
+        assert_eq!("trader-1", result.sub);
+    }
+
+    #[test]
+    fn test_expired_token() {
+        let verifier = create_verifier();
+        let token = encode(Some("k1"), &access_token(now() - 600));
+
+        assert_eq!(Some(JwtVerifyError::Expired), verifier.verify(&token).err());
+    }
+
+    #[test]
+    fn test_not_yet_valid_token() {
+        let verifier = create_verifier();
+        let mut access_token = access_token(now() + 600);
+        access_token.nbf = Some(now() + 300);
+
+        let token = encode(Some("k1"), &access_token);
+        assert!(matches!(
+            verifier.verify(&token),
+            Err(JwtVerifyError::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn test_wrong_audience_and_unknown_kid() {
+        let verifier = create_verifier();
+
+        let mut wrong_audience = access_token(now() + 60);
+        wrong_audience.aud = Some("other".to_string());
+        let token = encode(Some("k1"), &wrong_audience);
+        assert!(matches!(
+            verifier.verify(&token),
+            Err(JwtVerifyError::Invalid(_))
+        ));
+
+        let token = encode(Some("k2"), &access_token(now() + 60));
+        assert!(matches!(
+            verifier.verify(&token),
+            Err(JwtVerifyError::Invalid(_))
+        ));
+
+        let token = encode(None, &access_token(now() + 60));
+        assert!(matches!(
+            verifier.verify(&token),
+            Err(JwtVerifyError::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn test_key_rotation() {
+        let verifier = create_verifier();
+        verifier.get_keys().add_key(
+            "k2",
+            JwtKey::from_bytes(JwtAlgorithm::HS256, b"new-secret").unwrap(),
+        );
+        verifier.get_keys().set_default_kid(Some("k1".to_string()));
+
+        let token = encode(None, &access_token(now() + 60));
+        assert!(verifier.verify(&token).is_ok());
+
+        verifier.get_keys().remove_key("k1");
+        let token = encode(Some("k1"), &access_token(now() + 60));
+        assert!(verifier.verify(&token).is_err());
+    }
+
+    #[test]
+    fn test_revoked_session_revokes_token() {
+        let verifier = create_verifier();
+        let denylist = SessionDenylist::new(std::time::Duration::from_secs(60));
+
+        let mut token = access_token(now() + 60);
+        token.sid = Some("session".to_string());
+
+        let result = verifier.verify(&encode(Some("k1"), &token)).unwrap();
+        assert!(!result.is_revoked(&denylist, DateTimeAsMicroseconds::now()));
+
+        denylist.add("session", DateTimeAsMicroseconds::now());
+        assert!(result.is_revoked(&denylist, DateTimeAsMicroseconds::now()));
+    }
+}
@@ -0,0 +1,10 @@
+mod jwt_keys;
+pub use jwt_keys::*;
+mod jwt_access_token;
+pub use jwt_access_token::*;
+mod jwt_verifier;
+pub use jwt_verifier::*;
+mod jwt_request_credentials;
+pub use jwt_request_credentials::*;
+mod jwt_auth_middleware;
+pub use jwt_auth_middleware::*;
@@ -1,9 +1,25 @@
 mod api_result_status;
 mod get_client_id;
+mod request_state;
+mod result_status_registry;
+mod service_http_result;
+mod service_result_status;
+mod task_slot;
 
+pub mod api_keys;
+pub mod error_responses;
 pub mod middlewares;
+pub mod refresh_tokens;
+pub mod request_signing;
+pub mod session_store;
+pub mod session_tokens;
+pub mod step_up;
+pub mod totp;
 pub use api_result_status::*;
 pub use get_client_id::*;
+pub use result_status_registry::*;
+pub use service_http_result::*;
+pub use service_result_status::*;
 #[cfg(feature = "auth-middleware")]
 mod configure_rest_api_server;
 #[cfg(feature = "auth-middleware")]
@@ -14,6 +30,13 @@ mod configure_rest_api_server_with_no_auth_middleware;
 #[cfg(not(feature = "auth-middleware"))]
 pub use configure_rest_api_server_with_no_auth_middleware::*;
 
+#[cfg(feature = "jwt")]
+mod configure_rest_api_server_with_jwt;
+#[cfg(feature = "jwt")]
+pub mod jwt;
+#[cfg(feature = "jwt")]
+pub use configure_rest_api_server_with_jwt::*;
+
 mod get_country_code;
 pub mod http_fields;
 pub use get_country_code::*;
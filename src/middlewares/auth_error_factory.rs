@@ -1,54 +1,90 @@
-use my_http_server::controllers::{documentation::DataTypeProvider, AuthErrorFactory};
+use my_http_server::controllers::{
+    documentation::{out_results::HttpResult, DataTypeProvider},
+    AuthErrorFactory,
+};
 use my_http_server::macros::MyHttpObjectStructure;
 use serde::Serialize;
 use service_sdk::my_http_server;
 
-use crate::{ApiHttpResult, ApiResultStatus};
+use crate::error_responses::{ErrorResponse, ProblemDetails};
+use crate::{into_http_fail_result, ApiHttpResult, ApiResultStatus};
+
+use super::{take_auth_fail_reason, AuthFailReason};
 
 pub struct AuthErrorFactoryWl;
+
 #[derive(Serialize, MyHttpObjectStructure)]
 pub struct AccessClaimRequired {
     pub status: ApiResultStatus,
     pub data: String,
 }
 
+impl ErrorResponse for AccessClaimRequired {
+    fn get_result_status(&self) -> i16 {
+        self.status as i16
+    }
+
+    fn into_legacy_fail_result(self, message: Option<&str>) -> my_http_server::HttpFailResult {
+        into_http_fail_result(&self, &self.status, message)
+    }
+
+    fn into_problem_details(self) -> ProblemDetails {
+        ProblemDetails::new(&self.status).with_data(&serde_json::json!({ "claim": self.data }))
+    }
+}
+
 impl AuthErrorFactory for AuthErrorFactoryWl {
     fn get_not_authenticated(&self) -> my_http_server::HttpFailResult {
-        ApiResultStatus::AccessTokenExpired.into()
+        take_auth_fail_reason().get_api_result_status().into()
     }
 
     fn get_not_authorized(&self, claim_name: String) -> my_http_server::HttpFailResult {
-        let content = AccessClaimRequired {
+        AccessClaimRequired {
             status: ApiResultStatus::AccessClaimRequired,
             data: claim_name,
-        };
-        my_http_server::HttpFailResult::new(
-            my_http_server::WebContentType::Json,
-            403,
-            serde_json::to_vec(&content).unwrap(),
-            false,
-            false,
-        )
+        }
+        .into_fail_result()
     }
 
-    fn get_global_http_fail_result_types(
-        &self,
-    ) -> Option<Vec<my_http_server::controllers::documentation::out_results::HttpResult>> {
-        use my_http_server::controllers::documentation::out_results::HttpResult;
-        vec![
-            HttpResult {
-                http_code: 401,
-                nullable: false,
-                description: "Unauthenticated access".to_string(),
-                data_type: ApiHttpResult::get_data_type(),
-            },
-            HttpResult {
-                http_code: 403,
-                nullable: false,
-                description: "Unauthorized access".to_string(),
-                data_type: AccessClaimRequired::get_data_type(),
-            },
-        ]
-        .into()
+    fn get_global_http_fail_result_types(&self) -> Option<Vec<HttpResult>> {
+        let mut result: Vec<HttpResult> = AuthFailReason::TOKEN_REASONS
+            .into_iter()
+            .map(|reason| reason.get_fail_result_type(ApiHttpResult::get_data_type()))
+            .collect();
+
+        result.push(HttpResult {
+            http_code: 403,
+            nullable: false,
+            description: "Unauthorized access".to_string(),
+            data_type: AccessClaimRequired::get_data_type(),
+        });
+
+        result.into()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use service_sdk::my_http_server::controllers::AuthErrorFactory;
+
+    use super::AuthErrorFactoryWl;
+    use crate::middlewares::{record_auth_fail_reason, AuthFailReason, AuthFailResponseFactory};
+
+    #[tokio::test]
+    async fn test_factories_render_recorded_reason() {
+        tokio::spawn(async {
+            record_auth_fail_reason(AuthFailReason::CsrfTokenIsInvalid);
+            assert_eq!(AuthErrorFactoryWl.get_not_authenticated().status_code, 403);
+
+            record_auth_fail_reason(AuthFailReason::CsrfTokenIsInvalid);
+            assert_eq!(
+                AuthFailResponseFactory.get_not_authenticated().status_code,
+                403
+            );
+
+            assert_eq!(AuthErrorFactoryWl.get_not_authenticated().status_code, 401);
+        })
+        .await
+        .unwrap();
     }
 }
@@ -0,0 +1,206 @@
+use service_sdk::my_http_server::controllers::documentation::{
+    data_types::HttpDataType, out_results::HttpResult,
+};
+use service_sdk::my_http_server::{HttpFailResult, HttpOkResult};
+
+use crate::{
+    error_responses::{legacy_fail_result, ErrorResponse, ProblemDetails},
+    request_state::update_request_state,
+    ApiResultStatus,
+};
+
+use super::AuthenticationFailedApiResponse;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthFailReason {
+    TokenMissing,
+    TokenIsInvalid,
+    TokenExpired,
+    CsrfTokenIsInvalid,
+    ApiKeyIpIsNotAllowed,
+}
+
+impl AuthFailReason {
+    pub const ALL: [AuthFailReason; 5] = [
+        AuthFailReason::TokenMissing,
+        AuthFailReason::TokenIsInvalid,
+        AuthFailReason::TokenExpired,
+        AuthFailReason::CsrfTokenIsInvalid,
+        AuthFailReason::ApiKeyIpIsNotAllowed,
+    ];
+
+    // Any route which requires authentication can fail with these.
+    // CsrfTokenIsInvalid is documented only on routes called with the session cookie
+    pub const TOKEN_REASONS: [AuthFailReason; 3] = [
+        AuthFailReason::TokenMissing,
+        AuthFailReason::TokenIsInvalid,
+        AuthFailReason::TokenExpired,
+    ];
+
+    pub fn get_api_result_status(&self) -> ApiResultStatus {
+        match self {
+            AuthFailReason::TokenMissing => ApiResultStatus::AccessTokenMissing,
+            AuthFailReason::TokenIsInvalid => ApiResultStatus::TokenIsInvalid,
+            AuthFailReason::TokenExpired => ApiResultStatus::AccessTokenExpired,
+            AuthFailReason::CsrfTokenIsInvalid => ApiResultStatus::CsrfTokenIsInvalid,
+            AuthFailReason::ApiKeyIpIsNotAllowed => ApiResultStatus::ApiKeyIpIsNotAllowed,
+        }
+    }
+
+    pub fn get_description(&self) -> &'static str {
+        match self {
+            AuthFailReason::TokenMissing => "Access token is missing",
+            AuthFailReason::TokenIsInvalid => "Access token is invalid",
+            AuthFailReason::TokenExpired => "Access token is expired",
+            AuthFailReason::CsrfTokenIsInvalid => "X-CSRF-Token header is missing or invalid",
+            AuthFailReason::ApiKeyIpIsNotAllowed => "Api key is not allowed from this ip",
+        }
+    }
+
+    pub fn get_http_code(&self) -> u16 {
+        self.get_api_result_status().get_status_code()
+    }
+
+    // Documents the failure with the body an auth error factory renders it with.
+    // Routes called with the session cookie add it for CsrfTokenIsInvalid
+    pub fn get_fail_result_type(&self, data_type: HttpDataType) -> HttpResult {
+        HttpResult {
+            http_code: self.get_http_code(),
+            nullable: false,
+            description: format!(
+                "{}. Status: {:?}",
+                self.get_description(),
+                self.get_api_result_status()
+            ),
+            data_type,
+        }
+    }
+}
+
+impl ErrorResponse for AuthFailReason {
+    fn get_result_status(&self) -> i16 {
+        self.get_api_result_status() as i16
+    }
+
+    fn into_legacy_fail_result(self, message: Option<&str>) -> HttpFailResult {
+        let result = AuthenticationFailedApiResponse {
+            status: self.get_api_result_status(),
+            description: self.get_description().to_string(),
+        };
+
+        legacy_fail_result(&result, self.get_http_code(), false, message)
+    }
+
+    fn into_problem_details(self) -> ProblemDetails {
+        ProblemDetails::new(&self.get_api_result_status()).with_detail(self.get_description())
+    }
+}
+
+impl Into<HttpFailResult> for AuthFailReason {
+    fn into(self) -> HttpFailResult {
+        self.into_fail_result()
+    }
+}
+
+// AuthErrorFactory is called without HttpContext, so the reason is kept in the state of the
+// request until the factory renders it. The state is replaced by the next request
+pub fn record_auth_fail_reason(reason: AuthFailReason) {
+    update_request_state(|state| state.auth_fail_reason = Some(reason));
+}
+
+// Has to be called before the token is checked when ErrorResponseMiddleware is not installed,
+// so a reason of the previous request served by the same task is not rendered
+pub fn clear_auth_fail_reason() {
+    update_request_state(|state| state.auth_fail_reason = None);
+}
+
+// No reason recorded means the request came without a token
+pub fn take_auth_fail_reason() -> AuthFailReason {
+    update_request_state(|state| state.auth_fail_reason.take())
+        .flatten()
+        .unwrap_or(AuthFailReason::TokenMissing)
+}
+
+// Public routes are served without credentials.
+// Routes which require them get the recorded reason from AuthErrorFactory
+pub fn continue_unauthenticated(
+    reason: AuthFailReason,
+) -> Option<Result<HttpOkResult, HttpFailResult>> {
+    record_auth_fail_reason(reason);
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::error_responses::{
+        record_negotiated_error_response, ErrorResponseSettings, NegotiatedErrorResponse,
+    };
+
+    // Requests are served on spawned tasks. Outside of a task nothing is recorded
+    #[tokio::test]
+    async fn test_recorded_reason_is_taken_once() {
+        tokio::spawn(async {
+            record_auth_fail_reason(AuthFailReason::TokenExpired);
+
+            assert_eq!(take_auth_fail_reason(), AuthFailReason::TokenExpired);
+            assert_eq!(take_auth_fail_reason(), AuthFailReason::TokenMissing);
+        })
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_recorded_reason_is_per_task() {
+        tokio::spawn(async {
+            record_auth_fail_reason(AuthFailReason::TokenIsInvalid);
+        })
+        .await
+        .unwrap();
+
+        let reason = tokio::spawn(async { take_auth_fail_reason() });
+
+        assert_eq!(reason.await.unwrap(), AuthFailReason::TokenMissing);
+    }
+
+    #[tokio::test]
+    async fn test_cleared_reason() {
+        tokio::spawn(async {
+            record_auth_fail_reason(AuthFailReason::TokenExpired);
+            clear_auth_fail_reason();
+
+            assert_eq!(take_auth_fail_reason(), AuthFailReason::TokenMissing);
+        })
+        .await
+        .unwrap();
+    }
+
+    // Reasons of requests to public routes are not taken. The next request served by the task
+    // starts a new state
+    #[tokio::test]
+    async fn test_reason_is_dropped_by_next_request() {
+        tokio::spawn(async {
+            record_auth_fail_reason(AuthFailReason::TokenIsInvalid);
+
+            let settings = ErrorResponseSettings::new();
+
+            record_negotiated_error_response(Arc::new(NegotiatedErrorResponse {
+                format: settings.format,
+                settings: Arc::new(settings),
+                languages: Vec::new(),
+            }));
+
+            assert_eq!(take_auth_fail_reason(), AuthFailReason::TokenMissing);
+        })
+        .await
+        .unwrap();
+    }
+
+    #[test]
+    fn test_http_codes() {
+        assert_eq!(AuthFailReason::TokenExpired.get_http_code(), 401);
+        assert_eq!(AuthFailReason::CsrfTokenIsInvalid.get_http_code(), 403);
+    }
+}
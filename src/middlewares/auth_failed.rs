@@ -1,4 +1,7 @@
-use crate::ApiResultStatus;
+use crate::error_responses::{ErrorResponse, ProblemDetails};
+use crate::{into_http_fail_result, ApiResultStatus};
+
+use super::{take_auth_fail_reason, AuthFailReason};
 use my_http_server::macros::MyHttpObjectStructure;
 use my_http_server::HttpFailResult;
 use serde::Serialize;
@@ -18,16 +21,7 @@ pub struct AuthorizationFailedApiResponse {
 
 impl AuthorizationFailedApiResponse {
     pub fn new(status: ApiResultStatus, claim: String) -> HttpFailResult {
-        let result = AuthorizationFailedApiResponse { status, claim };
-
-        let content = serde_json::to_vec(&result).unwrap();
-        HttpFailResult::new(
-            my_http_server::WebContentType::Json,
-            403,
-            content,
-            false,
-            false,
-        )
+        AuthorizationFailedApiResponse { status, claim }.into_fail_result()
     }
 
     pub fn default_desc() -> String {
@@ -37,20 +31,11 @@ impl AuthorizationFailedApiResponse {
 
 impl AuthenticationFailedApiResponse {
     pub fn new(status: ApiResultStatus, description: String) -> HttpFailResult {
-        let result = AuthenticationFailedApiResponse {
+        AuthenticationFailedApiResponse {
             status,
             description,
-        };
-
-        let content = serde_json::to_vec(&result).unwrap();
-
-        HttpFailResult::new(
-            my_http_server::WebContentType::Json,
-            401,
-            content,
-            false,
-            false,
-        )
+        }
+        .into_fail_result()
     }
 
     pub fn default_desc() -> String {
@@ -58,6 +43,34 @@ impl AuthenticationFailedApiResponse {
     }
 }
 
+impl ErrorResponse for AuthorizationFailedApiResponse {
+    fn get_result_status(&self) -> i16 {
+        self.status as i16
+    }
+
+    fn into_legacy_fail_result(self, message: Option<&str>) -> HttpFailResult {
+        into_http_fail_result(&self, &self.status, message)
+    }
+
+    fn into_problem_details(self) -> ProblemDetails {
+        ProblemDetails::new(&self.status).with_data(&serde_json::json!({ "claim": self.claim }))
+    }
+}
+
+impl ErrorResponse for AuthenticationFailedApiResponse {
+    fn get_result_status(&self) -> i16 {
+        self.status as i16
+    }
+
+    fn into_legacy_fail_result(self, message: Option<&str>) -> HttpFailResult {
+        into_http_fail_result(&self, &self.status, message)
+    }
+
+    fn into_problem_details(self) -> ProblemDetails {
+        ProblemDetails::new(&self.status).with_detail(self.description)
+    }
+}
+
 use my_http_server::controllers::documentation::{
     data_types::HttpDataType, out_results::HttpResult,
 };
@@ -66,10 +79,7 @@ pub struct AuthFailResponseFactory;
 
 impl my_http_server::controllers::AuthErrorFactory for AuthFailResponseFactory {
     fn get_not_authenticated(&self) -> my_http_server::HttpFailResult {
-        return AuthenticationFailedApiResponse::new(
-            ApiResultStatus::AccessTokenExpired,
-            AuthenticationFailedApiResponse::default_desc(),
-        );
+        take_auth_fail_reason().into()
     }
 
     fn get_not_authorized(&self, claim_name: String) -> my_http_server::HttpFailResult {
@@ -79,24 +89,25 @@ impl my_http_server::controllers::AuthErrorFactory for AuthFailResponseFactory {
         );
     }
     fn get_global_http_fail_result_types(&self) -> Option<Vec<HttpResult>> {
-        let authentication_http_structure =
-            AuthenticationFailedApiResponse::get_http_data_structure();
         let authorization_http_structure =
             AuthorizationFailedApiResponse::get_http_data_structure();
 
-        Some(vec![
-            HttpResult {
-                http_code: 401,
-                nullable: false,
-                description: AuthenticationFailedApiResponse::default_desc(),
-                data_type: HttpDataType::Object(authentication_http_structure),
-            },
-            HttpResult {
-                http_code: 403,
-                nullable: false,
-                description: AuthorizationFailedApiResponse::default_desc(),
-                data_type: HttpDataType::Object(authorization_http_structure),
-            },
-        ])
+        let mut result: Vec<HttpResult> = AuthFailReason::TOKEN_REASONS
+            .into_iter()
+            .map(|reason| {
+                reason.get_fail_result_type(HttpDataType::Object(
+                    AuthenticationFailedApiResponse::get_http_data_structure(),
+                ))
+            })
+            .collect();
+
+        result.push(HttpResult {
+            http_code: 403,
+            nullable: false,
+            description: AuthorizationFailedApiResponse::default_desc(),
+            data_type: HttpDataType::Object(authorization_http_structure),
+        });
+
+        Some(result)
     }
 }
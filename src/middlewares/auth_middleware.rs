@@ -1,16 +1,85 @@
 use my_http_server::*;
-use service_sdk::{my_http_server, my_no_sql_sdk::reader::MyNoSqlDataReaderTcp};
+use service_sdk::my_http_server;
+use service_sdk::rust_extensions::date_time::DateTimeAsMicroseconds;
 use std::sync::Arc;
 
-use super::{GetSessionToken, SessionEntity, TradingPlatformRequestCredentials};
+use crate::{
+    session_store::{SessionDenylist, SessionStore},
+    session_tokens::SessionTokenFormat,
+};
+
+use super::{
+    clear_auth_fail_reason, continue_unauthenticated, AuthFailReason, ClaimsResolver,
+    CsrfProtection, ImpersonatedRequest, ImpersonationPolicy, IpBindingPolicy, SessionTokenSources,
+    SlidingExpiration, TradingPlatformRequestCredentials,
+};
 
 pub struct AuthMiddleware {
-    sessions_reader: Arc<MyNoSqlDataReaderTcp<SessionEntity>>,
+    sessions_store: Arc<dyn SessionStore + Send + Sync + 'static>,
+    ip_binding_policy: IpBindingPolicy,
+    sliding_expiration: Option<SlidingExpiration>,
+    denylist: Option<Arc<SessionDenylist>>,
+    token_format: Option<SessionTokenFormat>,
+    token_sources: SessionTokenSources,
+    csrf_protection: Option<CsrfProtection>,
+    claims_resolver: Option<Arc<ClaimsResolver>>,
+    impersonation_policy: ImpersonationPolicy,
 }
 
 impl AuthMiddleware {
-    pub fn new(sessions_reader: Arc<MyNoSqlDataReaderTcp<SessionEntity>>) -> Self {
-        Self { sessions_reader }
+    pub fn new(sessions_store: Arc<dyn SessionStore + Send + Sync + 'static>) -> Self {
+        Self {
+            sessions_store,
+            ip_binding_policy: IpBindingPolicy::default(),
+            sliding_expiration: None,
+            denylist: None,
+            token_format: None,
+            token_sources: SessionTokenSources::default(),
+            csrf_protection: None,
+            claims_resolver: None,
+            impersonation_policy: ImpersonationPolicy::default(),
+        }
+    }
+
+    pub fn with_ip_binding_policy(mut self, ip_binding_policy: IpBindingPolicy) -> Self {
+        self.ip_binding_policy = ip_binding_policy;
+        self
+    }
+
+    pub fn with_sliding_expiration(mut self, sliding_expiration: SlidingExpiration) -> Self {
+        self.sliding_expiration = Some(sliding_expiration);
+        self
+    }
+
+    pub fn with_denylist(mut self, denylist: Arc<SessionDenylist>) -> Self {
+        self.denylist = Some(denylist);
+        self
+    }
+
+    // Tokens which do not match the format are rejected without a store lookup
+    pub fn with_token_format(mut self, token_format: SessionTokenFormat) -> Self {
+        self.token_format = Some(token_format);
+        self
+    }
+
+    pub fn with_token_sources(mut self, token_sources: SessionTokenSources) -> Self {
+        self.token_sources = token_sources;
+        self
+    }
+
+    pub fn with_csrf_protection(mut self, csrf_protection: CsrfProtection) -> Self {
+        self.csrf_protection = Some(csrf_protection);
+        self
+    }
+
+    pub fn with_claims_resolver(mut self, claims_resolver: Arc<ClaimsResolver>) -> Self {
+        self.claims_resolver = Some(claims_resolver);
+        self
+    }
+
+    pub fn with_impersonation_policy(mut self, impersonation_policy: ImpersonationPolicy) -> Self {
+        self.impersonation_policy = impersonation_policy;
+        self
     }
 }
 
@@ -20,24 +89,84 @@ impl HttpServerMiddleware for AuthMiddleware {
         &self,
         ctx: &mut HttpContext,
     ) -> Option<Result<HttpOkResult, HttpFailResult>> {
-        let session_token = ctx.get_session_token();
+        clear_auth_fail_reason();
+
+        let session_token = match self.token_sources.get_session_token(ctx) {
+            Ok(session_token) => session_token?,
+            Err(_) => return continue_unauthenticated(AuthFailReason::TokenIsInvalid),
+        };
+
+        let from_cookie = session_token.from_cookie;
+        let session_token = session_token.value.as_str();
+
+        if let Some(token_format) = &self.token_format {
+            if !token_format.is_valid(session_token) {
+                return continue_unauthenticated(AuthFailReason::TokenIsInvalid);
+            }
+        }
+
+        let now = DateTimeAsMicroseconds::now();
+
+        if let Some(denylist) = &self.denylist {
+            if denylist.is_revoked(session_token, now) {
+                return continue_unauthenticated(AuthFailReason::TokenIsInvalid);
+            }
+        }
+
+        let token_entity = self.sessions_store.get_session(session_token).await;
+
+        let token_entity = match token_entity {
+            Some(token_entity) => token_entity,
+            None => return continue_unauthenticated(AuthFailReason::TokenIsInvalid),
+        };
+
+        if token_entity.is_expired(now) {
+            return continue_unauthenticated(AuthFailReason::TokenExpired);
+        }
 
-        if session_token.is_none() {
-            return None;
+        if from_cookie {
+            if let Some(csrf_protection) = &self.csrf_protection {
+                if let Err(reason) = csrf_protection.validate(ctx, &token_entity) {
+                    return continue_unauthenticated(reason);
+                }
+            }
         }
 
-        let token_entity = self
-            .sessions_reader
-            .get_entity(SessionEntity::PARTITION_KEY, session_token.unwrap())
-            .await;
+        let token_entity = match &self.sliding_expiration {
+            Some(sliding_expiration) => sliding_expiration
+                .try_extend(&token_entity, self.denylist.as_deref(), now)
+                .await
+                .unwrap_or(token_entity),
+            None => token_entity,
+        };
+
+        let request_ip = ctx.request.get_ip();
+        let request_ip = request_ip.get_real_ip();
+
+        let mut credentials = TradingPlatformRequestCredentials::new_with_claims_resolver(
+            token_entity,
+            request_ip,
+            &self.ip_binding_policy,
+            self.claims_resolver.as_deref(),
+        );
+
+        credentials.apply_impersonation_policy(&self.impersonation_policy);
 
-        if token_entity.is_none() {
-            return None;
+        if let Some(impersonator_id) = credentials.get_impersonator_id() {
+            if let Some(audit_hook) = &self.impersonation_policy.audit_hook {
+                audit_hook
+                    .on_impersonated_request(ImpersonatedRequest {
+                        trader_id: credentials.get_trader_id(),
+                        impersonator_id,
+                        method: ctx.request.method.as_str(),
+                        path: ctx.request.http_path.as_str(),
+                        ip: request_ip,
+                    })
+                    .await;
+            }
         }
 
-        ctx.credentials = Some(Box::new(TradingPlatformRequestCredentials::new(
-            token_entity.unwrap(),
-        )));
+        ctx.credentials = Some(Box::new(credentials));
 
         None
     }
@@ -0,0 +1,224 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::Deserialize;
+
+pub const WILDCARD_SUFFIX: &str = ".*";
+
+// Loaded from service settings, e.g.
+// { "roles": { "admin": ["trading.*", "support"] }, "knownClaims": ["trading.withdraw", "trading.deposit"] }
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClaimsResolverSettings {
+    #[serde(default)]
+    pub roles: HashMap<String, Vec<String>>,
+    #[serde(default)]
+    pub known_claims: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedClaim {
+    pub name: String,
+    pub expires: i64,
+    pub ip: Option<String>,
+}
+
+pub struct ClaimsResolver {
+    settings: ClaimsResolverSettings,
+}
+
+impl ClaimsResolver {
+    // A wildcard in a role which matches no known claim is most likely a typo, so it fails the startup
+    pub fn new(settings: ClaimsResolverSettings) -> Result<Self, String> {
+        for (role, role_claims) in settings.roles.iter() {
+            for role_claim in role_claims {
+                let Some(prefix) = get_wildcard_prefix(role_claim) else {
+                    continue;
+                };
+
+                if !settings
+                    .known_claims
+                    .iter()
+                    .any(|known_claim| matches_prefix(known_claim, prefix))
+                {
+                    return Err(format!(
+                        "Wildcard {} of role {} matches no known claim",
+                        role_claim, role
+                    ));
+                }
+            }
+        }
+
+        Ok(Self { settings })
+    }
+
+    pub fn from_json(src: &str) -> Result<Self, String> {
+        let settings: ClaimsResolverSettings = serde_json::from_str(src)
+            .map_err(|err| format!("Can not parse claims resolver settings. Err: {}", err))?;
+
+        Self::new(settings)
+    }
+
+    // Roles are expanded to their claims, `prefix.*` and `*` to every known claim under the prefix.
+    // Expanded claims inherit expiration and ip of the session claim they came from
+    pub fn resolve<'s>(
+        &self,
+        session_claims: impl Iterator<Item = (&'s str, i64, Option<&'s str>)>,
+    ) -> Vec<ResolvedClaim> {
+        let mut result: Vec<ResolvedClaim> = Vec::new();
+
+        for (name, expires, ip) in session_claims {
+            let mut expanded = HashSet::new();
+            self.expand(name, &mut expanded);
+
+            for name in expanded {
+                match result.iter_mut().find(|itm| itm.name == name) {
+                    Some(existing) => {
+                        if existing.expires < expires {
+                            existing.expires = expires;
+                            existing.ip = ip.map(|ip| ip.to_string());
+                        }
+                    }
+                    None => result.push(ResolvedClaim {
+                        name,
+                        expires,
+                        ip: ip.map(|ip| ip.to_string()),
+                    }),
+                }
+            }
+        }
+
+        result
+    }
+
+    fn expand(&self, name: &str, result: &mut HashSet<String>) {
+        if !result.insert(name.to_string()) {
+            return;
+        }
+
+        if let Some(role_claims) = self.settings.roles.get(name) {
+            for role_claim in role_claims {
+                self.expand(role_claim, result);
+            }
+        }
+
+        if let Some(prefix) = get_wildcard_prefix(name) {
+            for known_claim in self.settings.known_claims.iter() {
+                if matches_prefix(known_claim, prefix) {
+                    result.insert(known_claim.to_string());
+                }
+            }
+        }
+    }
+}
+
+fn get_wildcard_prefix(name: &str) -> Option<&str> {
+    if name == "*" {
+        return Some("");
+    }
+
+    name.strip_suffix(WILDCARD_SUFFIX)
+}
+
+fn matches_prefix(claim: &str, prefix: &str) -> bool {
+    if prefix.is_empty() {
+        return true;
+    }
+
+    match claim.strip_prefix(prefix) {
+        Some(rest) => rest.starts_with('.'),
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn create_resolver() -> ClaimsResolver {
+        ClaimsResolver::from_json(
+            r#"{
+                "roles": {
+                    "admin": ["trading.*", "support"],
+                    "support": ["users.read", "admin"]
+                },
+                "knownClaims": ["trading.withdraw", "trading.deposit", "trading2.withdraw", "users.read", "trading.orders.cancel"]
+            }"#,
+        )
+        .unwrap()
+    }
+
+    fn names(claims: &[ResolvedClaim]) -> Vec<&str> {
+        let mut result: Vec<_> = claims.iter().map(|itm| itm.name.as_str()).collect();
+        result.sort();
+        result
+    }
+
+    #[test]
+    fn test_wildcard() {
+        let resolver = create_resolver();
+        let result = resolver.resolve(vec![("trading.*", 10, None)].into_iter());
+
+        assert_eq!(
+            vec![
+                "trading.*",
+                "trading.deposit",
+                "trading.orders.cancel",
+                "trading.withdraw"
+            ],
+            names(&result)
+        );
+    }
+
+    #[test]
+    fn test_roles_with_cycle() {
+        let resolver = create_resolver();
+        let result = resolver.resolve(vec![("support", 10, Some("10.0.0.1"))].into_iter());
+
+        assert_eq!(
+            vec![
+                "admin",
+                "support",
+                "trading.*",
+                "trading.deposit",
+                "trading.orders.cancel",
+                "trading.withdraw",
+                "users.read"
+            ],
+            names(&result)
+        );
+        assert!(result
+            .iter()
+            .all(|itm| itm.expires == 10 && itm.ip.as_deref() == Some("10.0.0.1")));
+    }
+
+    #[test]
+    fn test_latest_expiration_wins() {
+        let resolver = create_resolver();
+        let result =
+            resolver.resolve(vec![("users.read", 10, None), ("support", 20, None)].into_iter());
+
+        let users_read = result.iter().find(|itm| itm.name == "users.read").unwrap();
+        assert_eq!(20, users_read.expires);
+    }
+
+    #[test]
+    fn test_plain_claims_are_kept() {
+        let resolver = ClaimsResolver::new(ClaimsResolverSettings::default()).unwrap();
+        let result = resolver.resolve(vec![("trading.withdraw", 10, None)].into_iter());
+
+        assert_eq!(vec!["trading.withdraw"], names(&result));
+    }
+
+    #[test]
+    fn test_wildcard_matching_no_known_claim_is_rejected() {
+        let result = ClaimsResolver::from_json(
+            r#"{ "roles": { "admin": ["tradng.*"] }, "knownClaims": ["trading.withdraw"] }"#,
+        );
+
+        assert!(result.is_err());
+
+        let result = ClaimsResolver::from_json(r#"{ "roles": { "admin": ["*"] } }"#);
+
+        assert!(result.is_err());
+    }
+}
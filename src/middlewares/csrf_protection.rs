@@ -0,0 +1,121 @@
+use service_sdk::my_http_server::HttpContext;
+
+use super::{find_cookie, get_header, AuthFailReason, SessionEntity};
+
+pub const CSRF_HEADER: &str = "x-csrf-token";
+
+const SAFE_METHODS: [&str; 4] = ["GET", "HEAD", "OPTIONS", "TRACE"];
+
+#[derive(Debug, Clone)]
+pub enum CsrfMode {
+    // X-CSRF-Token must match SessionEntity.csrf_token
+    SynchronizerToken,
+    // X-CSRF-Token must match the value of the named cookie
+    DoubleSubmitCookie { cookie_name: String },
+}
+
+// Checked by AuthMiddleware on unsafe methods when the session token came from a cookie.
+// Routes called with the cookie document the failure with AuthFailReason::CsrfTokenIsInvalid.get_fail_result_type
+#[derive(Debug, Clone)]
+pub struct CsrfProtection {
+    pub mode: CsrfMode,
+}
+
+impl CsrfProtection {
+    pub fn synchronizer_token() -> Self {
+        Self {
+            mode: CsrfMode::SynchronizerToken,
+        }
+    }
+
+    pub fn double_submit_cookie(cookie_name: impl Into<String>) -> Self {
+        Self {
+            mode: CsrfMode::DoubleSubmitCookie {
+                cookie_name: cookie_name.into(),
+            },
+        }
+    }
+
+    pub fn validate(
+        &self,
+        ctx: &HttpContext,
+        session_entity: &SessionEntity,
+    ) -> Result<(), AuthFailReason> {
+        self.validate_token(ctx, session_entity.csrf_token.as_deref())
+    }
+
+    // synchronizer_token is the one issued with the session; not used in double submit cookie mode
+    pub fn validate_token(
+        &self,
+        ctx: &HttpContext,
+        synchronizer_token: Option<&str>,
+    ) -> Result<(), AuthFailReason> {
+        if is_safe_method(ctx.request.method.as_str()) {
+            return Ok(());
+        }
+
+        let header_value = get_header(ctx, CSRF_HEADER)
+            .ok()
+            .flatten()
+            .map(|value| value.trim())
+            .filter(|value| !value.is_empty());
+
+        let expected = match &self.mode {
+            CsrfMode::SynchronizerToken => synchronizer_token,
+            CsrfMode::DoubleSubmitCookie { cookie_name } => get_header(ctx, "cookie")
+                .ok()
+                .flatten()
+                .and_then(|cookies| find_cookie(cookies, cookie_name)),
+        };
+
+        match (header_value, expected) {
+            (Some(header_value), Some(expected)) if constant_time_eq(header_value, expected) => {
+                Ok(())
+            }
+            _ => Err(AuthFailReason::CsrfTokenIsInvalid),
+        }
+    }
+}
+
+fn is_safe_method(method: &str) -> bool {
+    SAFE_METHODS
+        .iter()
+        .any(|safe| safe.eq_ignore_ascii_case(method))
+}
+
+pub(crate) fn constant_time_eq(left: &str, right: &str) -> bool {
+    let left = left.as_bytes();
+    let right = right.as_bytes();
+
+    if left.len() != right.len() {
+        return false;
+    }
+
+    let mut diff = 0u8;
+
+    for (l, r) in left.iter().zip(right.iter()) {
+        diff |= l ^ r;
+    }
+
+    diff == 0
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_safe_methods() {
+        assert!(is_safe_method("GET"));
+        assert!(is_safe_method("options"));
+        assert!(!is_safe_method("POST"));
+        assert!(!is_safe_method("DELETE"));
+    }
+
+    #[test]
+    fn test_constant_time_eq() {
+        assert!(constant_time_eq("abc", "abc"));
+        assert!(!constant_time_eq("abc", "abd"));
+        assert!(!constant_time_eq("abc", "abcd"));
+    }
+}
@@ -1,31 +1,247 @@
-use service_sdk::my_http_server::{HttpContext, HttpRequestHeaders};
+use service_sdk::my_http_server::HttpContext;
+use service_sdk::rust_extensions::StrOrString;
 
-const AUTH_HEADER: &str = "authorization";
+use super::{get_header, SessionTokenSources};
+
+pub(crate) const AUTH_HEADER: &str = "authorization";
+const BEARER_SCHEME: &str = "bearer";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthorizationHeaderError {
+    NotUtf8,
+    Empty,
+    UnsupportedScheme,
+    MissingToken,
+    InvalidTokenSyntax,
+}
 
 pub trait GetSessionToken {
+    // Authorization header only
     fn get_session_token(&self) -> Option<&str>;
+    fn try_get_session_token(&self) -> Result<Option<&str>, AuthorizationHeaderError>;
+    // Same sources and order AuthMiddleware is configured with
+    fn get_session_token_from_sources(
+        &self,
+        token_sources: &SessionTokenSources,
+    ) -> Option<StrOrString<'_>>;
 }
 
 impl GetSessionToken for HttpContext {
     fn get_session_token(&self) -> Option<&str> {
-        let auth_header = self.request.get_headers().try_get_case_sensitive(AUTH_HEADER)?;
-
-        let token = extract_token(auth_header.value)?;
+        self.try_get_session_token().ok()?
+    }
 
-        match std::str::from_utf8(token) {
-            Ok(result) => Some(result),
-            Err(_) => None,
+    fn try_get_session_token(&self) -> Result<Option<&str>, AuthorizationHeaderError> {
+        match get_header(self, AUTH_HEADER)? {
+            Some(auth_header) => parse_authorization_header(auth_header).map(Some),
+            None => Ok(None),
         }
     }
+
+    fn get_session_token_from_sources(
+        &self,
+        token_sources: &SessionTokenSources,
+    ) -> Option<StrOrString<'_>> {
+        let session_token = token_sources.get_session_token(self).ok()??;
+        Some(session_token.value)
+    }
+}
+
+// RFC 7235 credentials with RFC 6750 Bearer scheme: `Bearer <b64token>`.
+// Scheme is case-insensitive, any amount of spaces and tabs is allowed around parts
+pub fn parse_authorization_header(src: &str) -> Result<&str, AuthorizationHeaderError> {
+    let src = src.trim_matches(is_whitespace);
+
+    if src.is_empty() {
+        return Err(AuthorizationHeaderError::Empty);
+    }
+
+    let (scheme, token) = match src.find(is_whitespace) {
+        Some(index) => (
+            &src[..index],
+            src[index..].trim_start_matches(is_whitespace),
+        ),
+        None => (src, ""),
+    };
+
+    if !scheme.eq_ignore_ascii_case(BEARER_SCHEME) {
+        return Err(AuthorizationHeaderError::UnsupportedScheme);
+    }
+
+    if token.is_empty() {
+        return Err(AuthorizationHeaderError::MissingToken);
+    }
+
+    if !is_b64token(token) {
+        return Err(AuthorizationHeaderError::InvalidTokenSyntax);
+    }
+
+    Ok(token)
 }
 
-fn extract_token(src: &[u8]) -> Option<&[u8]> {
-    if src.len() < 6 {
-        return None;
+fn is_whitespace(c: char) -> bool {
+    c == ' ' || c == '\t'
+}
+
+// b64token = 1*( ALPHA / DIGIT / "-" / "." / "_" / "~" / "+" / "/" ) *"="
+pub(crate) fn is_b64token(src: &str) -> bool {
+    let body = src.trim_end_matches('=');
+
+    if body.is_empty() {
+        return false;
     }
 
-    if src[6] == b' ' {
-        return Some(&src[7..]);
+    body.bytes()
+        .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'.' | b'_' | b'~' | b'+' | b'/'))
+}
+
+#[cfg(test)]
+mod test {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::*;
+
+    #[test]
+    fn test_bearer_token() {
+        assert_eq!(Ok("abc"), parse_authorization_header("Bearer abc"));
+        assert_eq!(Ok("abc"), parse_authorization_header("bearer abc"));
+        assert_eq!(Ok("abc"), parse_authorization_header("BEARER abc"));
+        assert_eq!(Ok("abc"), parse_authorization_header("  Bearer \t  abc \t"));
+        assert_eq!(
+            Ok("a-b.c_d~e+f/g=="),
+            parse_authorization_header("Bearer a-b.c_d~e+f/g==")
+        );
+    }
+
+    #[test]
+    fn test_token_without_scheme() {
+        assert_eq!(
+            Err(AuthorizationHeaderError::UnsupportedScheme),
+            parse_authorization_header("abcdef")
+        );
+        assert_eq!(
+            Err(AuthorizationHeaderError::UnsupportedScheme),
+            parse_authorization_header(" abc ")
+        );
+    }
+
+    #[test]
+    fn test_short_headers() {
+        assert_eq!(
+            Err(AuthorizationHeaderError::UnsupportedScheme),
+            parse_authorization_header("Bearr")
+        );
+        assert_eq!(
+            Err(AuthorizationHeaderError::UnsupportedScheme),
+            parse_authorization_header("Bearer1")
+        );
+        assert_eq!(
+            Err(AuthorizationHeaderError::MissingToken),
+            parse_authorization_header("Bearer")
+        );
+        assert_eq!(
+            Err(AuthorizationHeaderError::MissingToken),
+            parse_authorization_header("Bearer ")
+        );
+        assert_eq!(
+            Err(AuthorizationHeaderError::Empty),
+            parse_authorization_header("")
+        );
+        assert_eq!(
+            Err(AuthorizationHeaderError::Empty),
+            parse_authorization_header(" \t ")
+        );
+    }
+
+    #[test]
+    fn test_other_schemes_are_rejected() {
+        assert_eq!(
+            Err(AuthorizationHeaderError::UnsupportedScheme),
+            parse_authorization_header("Basic dXNlcjpwYXNz")
+        );
+        assert_eq!(
+            Err(AuthorizationHeaderError::UnsupportedScheme),
+            parse_authorization_header("Token abcdef")
+        );
+    }
+
+    #[test]
+    fn test_invalid_token_syntax() {
+        assert_eq!(
+            Err(AuthorizationHeaderError::InvalidTokenSyntax),
+            parse_authorization_header("Bearer abc def")
+        );
+        assert_eq!(
+            Err(AuthorizationHeaderError::InvalidTokenSyntax),
+            parse_authorization_header("Bearer ===")
+        );
+        assert_eq!(
+            Err(AuthorizationHeaderError::InvalidTokenSyntax),
+            parse_authorization_header("Bearer ab=c")
+        );
+        assert_eq!(
+            Err(AuthorizationHeaderError::InvalidTokenSyntax),
+            parse_authorization_header("Bearer абв")
+        );
+    }
+
+    #[test]
+    fn test_random_input_never_panics() {
+        const ALPHABET: &[char] = &[
+            'B', 'e', 'a', 'r', 'b', 'E', 'A', 'R', ' ', '\t', '=', '-', '.', '/', '+', 'x', 'й',
+            '\u{0}', ',', '"',
+        ];
+
+        let mut rng = StdRng::seed_from_u64(7235);
+
+        for _ in 0..10_000 {
+            let len = rng.gen_range(0..16);
+            let src: String = (0..len)
+                .map(|_| ALPHABET[rng.gen_range(0..ALPHABET.len())])
+                .collect();
+
+            if let Ok(token) = parse_authorization_header(&src) {
+                assert!(!token.is_empty());
+                assert!(is_b64token(token));
+                assert!(src.contains(token));
+
+                let scheme = src.trim_matches(is_whitespace).split(is_whitespace).next();
+                assert!(scheme.unwrap().eq_ignore_ascii_case(BEARER_SCHEME));
+            }
+        }
+    }
+
+    #[test]
+    fn test_valid_tokens_roundtrip() {
+        const TOKEN_ALPHABET: &[u8] =
+            b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-._~+/";
+
+        let mut rng = StdRng::seed_from_u64(6750);
+
+        for _ in 0..1_000 {
+            let len = rng.gen_range(1..64);
+            let token: String = (0..len)
+                .map(|_| TOKEN_ALPHABET[rng.gen_range(0..TOKEN_ALPHABET.len())] as char)
+                .collect();
+
+            let separator = " ".repeat(rng.gen_range(1..4));
+            let header = format!("Bearer{}{}", separator, token);
+
+            assert_eq!(Ok(token.as_str()), parse_authorization_header(&header));
+
+            for scheme in ["Basic", "Bearer1", "Token"] {
+                let header = format!("{}{}{}", scheme, separator, token);
+
+                assert_eq!(
+                    Err(AuthorizationHeaderError::UnsupportedScheme),
+                    parse_authorization_header(&header)
+                );
+            }
+
+            assert_eq!(
+                Err(AuthorizationHeaderError::UnsupportedScheme),
+                parse_authorization_header(&token)
+            );
+        }
     }
-    Some(src)
 }
@@ -0,0 +1,79 @@
+use std::{collections::HashSet, sync::Arc};
+
+pub struct ImpersonatedRequest<'s> {
+    pub trader_id: &'s str,
+    pub impersonator_id: &'s str,
+    pub method: &'s str,
+    pub path: &'s str,
+    pub ip: &'s str,
+}
+
+#[async_trait::async_trait]
+pub trait ImpersonationAuditHook {
+    // Called for every request made with an impersonated session before it reaches the controller
+    async fn on_impersonated_request(&self, request: ImpersonatedRequest<'_>);
+}
+
+// Nothing is denied by default. Each service lists the claims of its money moving routes
+pub struct ImpersonationPolicy {
+    // Exact claim names which are never granted to an impersonated session
+    pub denied_claims: HashSet<String>,
+    pub audit_hook: Option<Arc<dyn ImpersonationAuditHook + Send + Sync + 'static>>,
+}
+
+impl ImpersonationPolicy {
+    pub fn new() -> Self {
+        Self {
+            denied_claims: HashSet::new(),
+            audit_hook: None,
+        }
+    }
+
+    pub fn deny_claim(mut self, claim_name: impl Into<String>) -> Self {
+        self.denied_claims.insert(claim_name.into());
+        self
+    }
+
+    pub fn with_audit_hook(
+        mut self,
+        audit_hook: Arc<dyn ImpersonationAuditHook + Send + Sync + 'static>,
+    ) -> Self {
+        self.audit_hook = Some(audit_hook);
+        self
+    }
+
+    pub fn is_claim_denied(&self, claim_name: &str, session_restricted_claims: &[String]) -> bool {
+        if session_restricted_claims
+            .iter()
+            .any(|restricted| restricted == claim_name)
+        {
+            return true;
+        }
+
+        self.denied_claims.contains(claim_name)
+    }
+}
+
+impl Default for ImpersonationPolicy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::ImpersonationPolicy;
+
+    #[test]
+    fn test_only_listed_claims_are_denied() {
+        let policy = ImpersonationPolicy::new()
+            .deny_claim("trading.withdraw")
+            .deny_claim("crypto.transfer");
+
+        assert!(policy.is_claim_denied("trading.withdraw", &[]));
+        assert!(policy.is_claim_denied("crypto.transfer", &[]));
+        assert!(!policy.is_claim_denied("trading.withdraw.history", &[]));
+        assert!(!policy.is_claim_denied("profile.read", &[]));
+        assert!(policy.is_claim_denied("profile.read", &["profile.read".to_string()]));
+    }
+}
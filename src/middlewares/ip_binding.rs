@@ -0,0 +1,171 @@
+use std::net::IpAddr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IpBindingMode {
+    Off,
+    Strict,
+    // Same /24 for IPv4 and same /64 for IPv6
+    Subnet,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct IpBindingPolicy {
+    pub mode: IpBindingMode,
+    pub fallback_to_session_ip: bool,
+}
+
+impl IpBindingPolicy {
+    pub fn off() -> Self {
+        Self {
+            mode: IpBindingMode::Off,
+            fallback_to_session_ip: false,
+        }
+    }
+
+    pub fn strict() -> Self {
+        Self {
+            mode: IpBindingMode::Strict,
+            fallback_to_session_ip: false,
+        }
+    }
+
+    pub fn subnet() -> Self {
+        Self {
+            mode: IpBindingMode::Subnet,
+            fallback_to_session_ip: false,
+        }
+    }
+
+    pub fn with_session_ip_fallback(mut self) -> Self {
+        self.fallback_to_session_ip = true;
+        self
+    }
+
+    // Returns list of ips the claim is allowed from. None - claim is not bound to ip
+    pub fn get_allowed_ips(
+        &self,
+        claim_ip: Option<&str>,
+        session_ip: Option<&str>,
+        request_ip: &str,
+    ) -> Option<Vec<String>> {
+        if self.mode == IpBindingMode::Off {
+            return None;
+        }
+
+        let bound_ip = match claim_ip {
+            Some(claim_ip) => claim_ip,
+            None => {
+                if !self.fallback_to_session_ip {
+                    return None;
+                }
+                session_ip?
+            }
+        };
+
+        if self.ip_matches(bound_ip, request_ip) {
+            return Some(vec![request_ip.to_string()]);
+        }
+
+        Some(vec![bound_ip.to_string()])
+    }
+
+    pub fn ip_matches(&self, bound_ip: &str, request_ip: &str) -> bool {
+        match self.mode {
+            IpBindingMode::Off => true,
+            IpBindingMode::Strict => ips_are_equal(bound_ip, request_ip),
+            IpBindingMode::Subnet => ips_are_in_same_subnet(bound_ip, request_ip),
+        }
+    }
+}
+
+// Claims are not bound to ip unless strict or subnet mode is set explicitly
+impl Default for IpBindingPolicy {
+    fn default() -> Self {
+        Self::off()
+    }
+}
+
+fn ips_are_equal(left: &str, right: &str) -> bool {
+    match (left.parse::<IpAddr>(), right.parse::<IpAddr>()) {
+        (Ok(left), Ok(right)) => left == right,
+        _ => left.trim() == right.trim(),
+    }
+}
+
+fn ips_are_in_same_subnet(left: &str, right: &str) -> bool {
+    match (left.parse::<IpAddr>(), right.parse::<IpAddr>()) {
+        (Ok(IpAddr::V4(left)), Ok(IpAddr::V4(right))) => left.octets()[..3] == right.octets()[..3],
+        (Ok(IpAddr::V6(left)), Ok(IpAddr::V6(right))) => {
+            left.segments()[..4] == right.segments()[..4]
+        }
+        (Ok(_), Ok(_)) => false,
+        _ => left.trim() == right.trim(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_strict_mode() {
+        let policy = IpBindingPolicy::strict();
+
+        assert!(policy.ip_matches("10.0.0.1", "10.0.0.1"));
+        assert!(!policy.ip_matches("10.0.0.1", "10.0.0.2"));
+        assert!(policy.ip_matches("2001:db8::1", "2001:0db8:0:0::1"));
+    }
+
+    #[test]
+    fn test_subnet_mode() {
+        let policy = IpBindingPolicy::subnet();
+
+        assert!(policy.ip_matches("10.0.0.1", "10.0.0.254"));
+        assert!(!policy.ip_matches("10.0.0.1", "10.0.1.1"));
+        assert!(policy.ip_matches("2001:db8:0:1::1", "2001:db8:0:1:ffff::2"));
+        assert!(!policy.ip_matches("2001:db8:0:1::1", "2001:db8:0:2::1"));
+        assert!(!policy.ip_matches("10.0.0.1", "::ffff:10.0.0.1"));
+    }
+
+    #[test]
+    fn test_allowed_ips() {
+        let policy = IpBindingPolicy::subnet();
+
+        assert_eq!(
+            policy.get_allowed_ips(Some("10.0.0.1"), None, "10.0.0.7"),
+            Some(vec!["10.0.0.7".to_string()])
+        );
+
+        assert_eq!(
+            policy.get_allowed_ips(Some("10.0.0.1"), None, "10.0.1.7"),
+            Some(vec!["10.0.0.1".to_string()])
+        );
+
+        assert_eq!(
+            policy.get_allowed_ips(None, Some("10.0.0.1"), "10.0.1.7"),
+            None
+        );
+
+        let policy = policy.with_session_ip_fallback();
+        assert_eq!(
+            policy.get_allowed_ips(None, Some("10.0.0.1"), "10.0.1.7"),
+            Some(vec!["10.0.0.1".to_string()])
+        );
+
+        assert_eq!(
+            IpBindingPolicy::off().get_allowed_ips(Some("10.0.0.1"), None, "10.0.1.7"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_default_is_off() {
+        let policy = IpBindingPolicy::default();
+
+        assert_eq!(policy.mode, IpBindingMode::Off);
+        assert_eq!(
+            policy.get_allowed_ips(Some("10.0.0.1"), Some("10.0.0.1"), "10.0.1.7"),
+            None
+        );
+    }
+}
@@ -3,12 +3,26 @@ mod auth_middleware;
 #[cfg(feature = "auth-middleware")]
 pub use auth_middleware::*;
 mod auth_error_factory;
+mod auth_fail_reason;
 mod auth_failed;
+mod claims_resolver;
+mod csrf_protection;
 mod get_session_token;
+mod impersonation;
+mod ip_binding;
 mod request_creds;
 mod session_entity;
+mod session_token_sources;
+mod sliding_expiration;
 pub use auth_error_factory::*;
+pub use auth_fail_reason::*;
 pub use auth_failed::*;
+pub use claims_resolver::*;
+pub use csrf_protection::*;
 pub use get_session_token::*;
+pub use impersonation::*;
+pub use ip_binding::*;
 pub use request_creds::*;
 pub use session_entity::*;
+pub use session_token_sources::*;
+pub use sliding_expiration::*;
@@ -4,15 +4,122 @@ use service_sdk::my_http_server;
 use service_sdk::my_http_server::{RequestClaim, RequestCredentials};
 use service_sdk::rust_extensions::date_time::DateTimeAsMicroseconds;
 
-use super::SessionEntity;
+use super::{ClaimsResolver, ImpersonationPolicy, IpBindingPolicy, ResolvedClaim, SessionEntity};
+
+struct CredentialsClaim {
+    name: String,
+    expires: i64,
+    allowed_ips: Option<Vec<String>>,
+}
 
 pub struct TradingPlatformRequestCredentials {
     pub session_entity: Arc<SessionEntity>,
+    // None when the session has no claims
+    claims: Option<Vec<CredentialsClaim>>,
 }
 
 impl TradingPlatformRequestCredentials {
     pub fn new(session_entity: Arc<SessionEntity>) -> Self {
-        Self { session_entity }
+        let claims = session_entity.claims.as_ref().map(|claims| {
+            claims
+                .iter()
+                .map(|claim| CredentialsClaim {
+                    name: claim.name.clone(),
+                    expires: claim.expires,
+                    allowed_ips: None,
+                })
+                .collect()
+        });
+
+        Self {
+            session_entity,
+            claims,
+        }
+    }
+
+    pub fn new_with_ip_binding(
+        session_entity: Arc<SessionEntity>,
+        request_ip: &str,
+        ip_binding_policy: &IpBindingPolicy,
+    ) -> Self {
+        Self::new_with_claims_resolver(session_entity, request_ip, ip_binding_policy, None)
+    }
+
+    pub fn new_with_claims_resolver(
+        session_entity: Arc<SessionEntity>,
+        request_ip: &str,
+        ip_binding_policy: &IpBindingPolicy,
+        claims_resolver: Option<&ClaimsResolver>,
+    ) -> Self {
+        let Some(session_claims) = session_entity.claims.as_ref() else {
+            return Self {
+                session_entity,
+                claims: None,
+            };
+        };
+
+        let resolved_claims = match claims_resolver {
+            Some(claims_resolver) => claims_resolver.resolve(
+                session_claims
+                    .iter()
+                    .map(|claim| (claim.name.as_str(), claim.expires, claim.ip.as_deref())),
+            ),
+            None => session_claims
+                .iter()
+                .map(|claim| ResolvedClaim {
+                    name: claim.name.clone(),
+                    expires: claim.expires,
+                    ip: claim.ip.clone(),
+                })
+                .collect(),
+        };
+
+        let claims = resolved_claims
+            .into_iter()
+            .map(|claim| CredentialsClaim {
+                allowed_ips: ip_binding_policy.get_allowed_ips(
+                    claim.ip.as_deref(),
+                    session_entity.ip.as_deref(),
+                    request_ip,
+                ),
+                name: claim.name,
+                expires: claim.expires,
+            })
+            .collect();
+
+        Self {
+            session_entity,
+            claims: Some(claims),
+        }
+    }
+}
+
+impl TradingPlatformRequestCredentials {
+    pub fn get_trader_id(&self) -> &str {
+        &self.session_entity.trader_id
+    }
+
+    pub fn get_impersonator_id(&self) -> Option<&str> {
+        self.session_entity.impersonator_id.as_deref()
+    }
+
+    // Does nothing for sessions which are not impersonated
+    pub fn apply_impersonation_policy(&mut self, impersonation_policy: &ImpersonationPolicy) {
+        if !self.session_entity.is_impersonated() {
+            return;
+        }
+
+        let restricted_claims = self
+            .session_entity
+            .restricted_claims
+            .as_deref()
+            .unwrap_or_default();
+
+        if let Some(claims) = self.claims.as_mut() {
+            claims.retain(|claim| {
+                !impersonation_policy.is_claim_denied(&claim.name, restricted_claims)
+            });
+        }
     }
 }
 
@@ -22,15 +129,15 @@ impl RequestCredentials for TradingPlatformRequestCredentials {
     }
 
     fn get_claims<'s>(&'s self) -> Option<Vec<my_http_server::RequestClaim<'s>>> {
-        let session_claims = self.session_entity.claims.as_ref()?;
+        let claims = self.claims.as_ref()?;
 
-        let mut result = Vec::new();
+        let mut result = Vec::with_capacity(claims.len());
 
-        for session_claim in session_claims {
+        for claim in claims {
             result.push(RequestClaim {
-                id: &session_claim.name,
-                expires: DateTimeAsMicroseconds::new(session_claim.expires),
-                allowed_ips: None,
+                id: &claim.name,
+                expires: DateTimeAsMicroseconds::new(claim.expires),
+                allowed_ips: claim.allowed_ips.as_ref(),
             });
         }
 
@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use service_sdk::{my_no_sql_sdk, rust_extensions::date_time::DateTimeAsMicroseconds};
 
+use crate::session_tokens::SessionTokenGenerator;
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct SessionClaim {
     pub name: String,
@@ -24,19 +26,86 @@ pub struct SessionEntity {
     pub ip: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub user_agent: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub csrf_token: Option<String>,
+    // Set when a support agent acts as the trader
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub impersonator_id: Option<String>,
+    // Claims which are not granted while impersonating, in addition to ImpersonationPolicy
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub restricted_claims: Option<Vec<String>>,
 }
 
 impl SessionEntity {
     pub const PARTITION_KEY: &'static str = "t";
 
+    pub fn new(session_token: String, trader_id: String, expires: DateTimeAsMicroseconds) -> Self {
+        Self {
+            partition_key: Self::PARTITION_KEY.to_string(),
+            row_key: session_token,
+            time_stamp: Default::default(),
+            expires: expires.into(),
+            trader_id,
+            claims: None,
+            country: None,
+            ip: None,
+            user_agent: None,
+            created: Some(DateTimeAsMicroseconds::now().unix_microseconds),
+            csrf_token: None,
+            impersonator_id: None,
+            restricted_claims: None,
+        }
+    }
+
     pub fn get_session_token(&self) -> &str {
         &self.row_key
     }
 
+    pub fn get_expires(&self) -> Option<DateTimeAsMicroseconds> {
+        let expires: DateTimeAsMicroseconds = self.expires.into();
+
+        if expires.unix_microseconds <= 0 {
+            return None;
+        }
+
+        Some(expires)
+    }
+
+    pub fn is_impersonated(&self) -> bool {
+        self.impersonator_id.is_some()
+    }
+
+    // Sessions written before `created` was introduced are ordered by expiration
+    pub fn get_created_or_expires(&self) -> i64 {
+        if let Some(created) = self.created {
+            return created;
+        }
+
+        match self.get_expires() {
+            Some(expires) => expires.unix_microseconds,
+            None => 0,
+        }
+    }
+
+    pub fn is_expired(&self, now: DateTimeAsMicroseconds) -> bool {
+        match self.get_expires() {
+            Some(expires) => expires.unix_microseconds <= now.unix_microseconds,
+            None => false,
+        }
+    }
+
     pub fn extend_expiration(&mut self, new_expiration: DateTimeAsMicroseconds) {
         self.expires = new_expiration.into();
     }
 
+    // Token has to be handed to the web client which sends it back as X-CSRF-Token header
+    pub fn generate_csrf_token(&mut self, generator: &SessionTokenGenerator) -> &str {
+        self.csrf_token = Some(generator.generate());
+        self.csrf_token.as_deref().unwrap()
+    }
+
     pub fn set_claim(&mut self, name: String, expires: DateTimeAsMicroseconds, ip: Option<String>) {
         if self.claims.is_none() {
             self.claims = Some(vec![SessionClaim {
@@ -0,0 +1,210 @@
+use service_sdk::my_http_server::{HttpContext, HttpRequestHeaders};
+use service_sdk::rust_extensions::StrOrString;
+
+use super::{is_b64token, parse_authorization_header, AuthorizationHeaderError, AUTH_HEADER};
+
+const COOKIE_HEADER: &str = "cookie";
+const WEB_SOCKET_PROTOCOL_HEADER: &str = "sec-websocket-protocol";
+
+#[derive(Debug, Clone)]
+pub enum SessionTokenSource {
+    AuthorizationHeader,
+    Cookie(String),
+    // The request url, token included, is written to access logs and telemetry by the http server
+    // and by proxies in front of it. Enable it only for endpoints which can not send headers
+    // (WebSocket, SSE) and keep such tokens short lived
+    QueryParameter(String),
+    // Browsers can not set headers for WebSocket, so the token goes as the protocol
+    // right after the marker: `Sec-WebSocket-Protocol: {marker}, {token}`
+    WebSocketProtocol { marker: String },
+}
+
+impl SessionTokenSource {
+    pub fn is_cookie(&self) -> bool {
+        matches!(self, SessionTokenSource::Cookie(_))
+    }
+}
+
+pub struct SessionToken<'s> {
+    pub value: StrOrString<'s>,
+    pub from_cookie: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct SessionTokenSourceItem {
+    pub source: SessionTokenSource,
+    pub enabled: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct SessionTokenSources {
+    items: Vec<SessionTokenSourceItem>,
+}
+
+impl SessionTokenSources {
+    pub fn new() -> Self {
+        Self { items: Vec::new() }
+    }
+
+    pub fn add(mut self, source: SessionTokenSource, enabled: bool) -> Self {
+        self.items.push(SessionTokenSourceItem { source, enabled });
+        self
+    }
+
+    pub fn get_items(&self) -> &[SessionTokenSourceItem] {
+        &self.items
+    }
+
+    pub fn uses_cookies(&self) -> bool {
+        self.items
+            .iter()
+            .any(|itm| itm.enabled && itm.source.is_cookie())
+    }
+
+    // Sources are checked in the order they were added. First source which has a token wins
+    pub fn get_session_token<'s>(
+        &self,
+        ctx: &'s HttpContext,
+    ) -> Result<Option<SessionToken<'s>>, AuthorizationHeaderError> {
+        for item in self.items.iter() {
+            if !item.enabled {
+                continue;
+            }
+
+            let token = match &item.source {
+                SessionTokenSource::AuthorizationHeader => get_header(ctx, AUTH_HEADER)?
+                    .map(parse_authorization_header)
+                    .transpose()?
+                    .map(StrOrString::create_as_str),
+                SessionTokenSource::Cookie(name) => get_header(ctx, COOKIE_HEADER)?
+                    .and_then(|cookies| find_cookie(cookies, name))
+                    .map(StrOrString::create_as_str),
+                SessionTokenSource::QueryParameter(name) => {
+                    get_query_parameter(ctx, name)?.map(StrOrString::create_as_string)
+                }
+                SessionTokenSource::WebSocketProtocol { marker } => {
+                    get_header(ctx, WEB_SOCKET_PROTOCOL_HEADER)?
+                        .and_then(|protocols| find_web_socket_protocol_token(protocols, marker))
+                        .map(StrOrString::create_as_str)
+                }
+            };
+
+            if let Some(token) = token {
+                if !is_b64token(token.as_str()) {
+                    return Err(AuthorizationHeaderError::InvalidTokenSyntax);
+                }
+
+                return Ok(Some(SessionToken {
+                    value: token,
+                    from_cookie: item.source.is_cookie(),
+                }));
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+impl Default for SessionTokenSources {
+    fn default() -> Self {
+        Self::new().add(SessionTokenSource::AuthorizationHeader, true)
+    }
+}
+
+pub(crate) fn get_header<'s>(
+    ctx: &'s HttpContext,
+    name: &str,
+) -> Result<Option<&'s str>, AuthorizationHeaderError> {
+    ctx.request
+        .get_headers()
+        .try_get_case_insensitive_as_str(name)
+        .map_err(|_| AuthorizationHeaderError::NotUtf8)
+}
+
+fn get_query_parameter(
+    ctx: &HttpContext,
+    name: &str,
+) -> Result<Option<String>, AuthorizationHeaderError> {
+    let query_string = match ctx.request.get_query_string() {
+        Ok(query_string) => query_string,
+        Err(_) => return Ok(None),
+    };
+
+    match query_string.get_optional(name) {
+        Some(value) => value
+            .as_string()
+            .map(Some)
+            .map_err(|_| AuthorizationHeaderError::InvalidTokenSyntax),
+        None => Ok(None),
+    }
+}
+
+pub(crate) fn find_cookie<'s>(cookies: &'s str, name: &str) -> Option<&'s str> {
+    for cookie in cookies.split(';') {
+        let (cookie_name, value) = match cookie.trim().split_once('=') {
+            Some(pair) => pair,
+            None => continue,
+        };
+
+        if cookie_name.trim() != name {
+            continue;
+        }
+
+        let value = value.trim();
+        let value = value
+            .strip_prefix('"')
+            .and_then(|value| value.strip_suffix('"'))
+            .unwrap_or(value);
+
+        if value.is_empty() {
+            return None;
+        }
+
+        return Some(value);
+    }
+
+    None
+}
+
+fn find_web_socket_protocol_token<'s>(protocols: &'s str, marker: &str) -> Option<&'s str> {
+    let mut protocols = protocols.split(',').map(|itm| itm.trim());
+
+    while let Some(protocol) = protocols.next() {
+        if protocol == marker {
+            return protocols.next().filter(|token| !token.is_empty());
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_find_cookie() {
+        let cookies = "theme=dark; session=abc123; other=\"quoted\"";
+
+        assert_eq!(Some("abc123"), find_cookie(cookies, "session"));
+        assert_eq!(Some("quoted"), find_cookie(cookies, "other"));
+        assert_eq!(None, find_cookie(cookies, "sess"));
+        assert_eq!(None, find_cookie("session=", "session"));
+    }
+
+    #[test]
+    fn test_find_web_socket_protocol_token() {
+        assert_eq!(
+            Some("abc123"),
+            find_web_socket_protocol_token("graphql-ws, access_token, abc123", "access_token")
+        );
+        assert_eq!(
+            None,
+            find_web_socket_protocol_token("access_token", "access_token")
+        );
+        assert_eq!(
+            None,
+            find_web_socket_protocol_token("graphql-ws, abc123", "access_token")
+        );
+    }
+}
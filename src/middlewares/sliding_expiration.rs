@@ -0,0 +1,247 @@
+use std::{collections::HashMap, sync::Arc, sync::Mutex, time::Duration};
+
+use service_sdk::rust_extensions::date_time::DateTimeAsMicroseconds;
+
+use crate::session_store::{SessionDenylist, SessionWriter};
+
+use super::SessionEntity;
+
+pub struct SlidingExpiration {
+    // Session is extended when it expires sooner than this window
+    pub extend_window: Duration,
+    // New expiration is now + session_lifetime
+    pub session_lifetime: Duration,
+    // Minimal interval between two write-backs of the same session
+    pub write_throttle: Duration,
+    writer: Arc<dyn SessionWriter + Send + Sync + 'static>,
+    last_writes: Mutex<HashMap<String, i64>>,
+}
+
+impl SlidingExpiration {
+    pub fn new(
+        writer: Arc<dyn SessionWriter + Send + Sync + 'static>,
+        extend_window: Duration,
+        session_lifetime: Duration,
+    ) -> Self {
+        Self {
+            extend_window,
+            session_lifetime,
+            write_throttle: Duration::from_secs(30),
+            writer,
+            last_writes: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn with_write_throttle(mut self, write_throttle: Duration) -> Self {
+        self.write_throttle = write_throttle;
+        self
+    }
+
+    pub fn should_extend(
+        &self,
+        session_entity: &SessionEntity,
+        now: DateTimeAsMicroseconds,
+    ) -> bool {
+        let expires = match session_entity.get_expires() {
+            Some(expires) => expires,
+            None => return false,
+        };
+
+        expires
+            .unix_microseconds
+            .saturating_sub(now.unix_microseconds)
+            <= duration_as_micros(self.extend_window)
+    }
+
+    // Returns extended session if it was written back.
+    // Only the expiration of the stored session is updated, so claims granted since the session
+    // was read are kept and a session deleted in between is not written back
+    pub async fn try_extend(
+        &self,
+        session_entity: &SessionEntity,
+        denylist: Option<&SessionDenylist>,
+        now: DateTimeAsMicroseconds,
+    ) -> Option<Arc<SessionEntity>> {
+        if !self.should_extend(session_entity, now) {
+            return None;
+        }
+
+        let session_token = session_entity.get_session_token();
+
+        if !self.try_acquire_write(session_token, now) {
+            return None;
+        }
+
+        // Session could be revoked while it was read
+        if let Some(denylist) = denylist {
+            if denylist.is_revoked(session_token, now) {
+                return None;
+            }
+        }
+
+        let new_expiration = DateTimeAsMicroseconds::new(
+            now.unix_microseconds
+                .saturating_add(duration_as_micros(self.session_lifetime)),
+        );
+
+        // Session keeps its current expiration. Write-back is retried once the throttle passes
+        let extended = self
+            .writer
+            .update_session(session_token, &|session_entity| {
+                session_entity.extend_expiration(new_expiration)
+            })
+            .await
+            .ok()??;
+
+        Some(Arc::new(extended))
+    }
+
+    fn try_acquire_write(&self, session_token: &str, now: DateTimeAsMicroseconds) -> bool {
+        let throttle = duration_as_micros(self.write_throttle);
+        let mut last_writes = self.last_writes.lock().unwrap();
+
+        if let Some(last_write) = last_writes.get(session_token) {
+            if now.unix_microseconds - *last_write < throttle {
+                return false;
+            }
+        }
+
+        last_writes.retain(|_, last_write| now.unix_microseconds - *last_write < throttle);
+        last_writes.insert(session_token.to_string(), now.unix_microseconds);
+
+        true
+    }
+}
+
+// Durations which do not fit into i64 microseconds are treated as infinite
+pub(crate) fn duration_as_micros(duration: Duration) -> i64 {
+    i64::try_from(duration.as_micros()).unwrap_or(i64::MAX)
+}
+
+#[cfg(test)]
+mod test {
+    use std::{sync::Arc, time::Duration};
+
+    use service_sdk::rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use super::{duration_as_micros, SessionEntity, SlidingExpiration};
+    use crate::session_store::{InMemorySessionStore, SessionDenylist, SessionStore};
+
+    #[test]
+    fn test_write_back_is_throttled_per_session() {
+        let sliding_expiration = SlidingExpiration::new(
+            Arc::new(InMemorySessionStore::new()),
+            Duration::from_secs(60 * 10),
+            Duration::from_secs(60 * 60),
+        );
+
+        let now = DateTimeAsMicroseconds::new(1_000_000_000);
+        let later = DateTimeAsMicroseconds::new(now.unix_microseconds + 1_000_000);
+        let after_throttle = DateTimeAsMicroseconds::new(now.unix_microseconds + 31_000_000);
+
+        assert!(sliding_expiration.try_acquire_write("token-1", now));
+        assert!(!sliding_expiration.try_acquire_write("token-1", later));
+        assert!(sliding_expiration.try_acquire_write("token-2", later));
+        assert!(sliding_expiration.try_acquire_write("token-1", after_throttle));
+    }
+
+    #[test]
+    fn test_duration_as_micros() {
+        assert_eq!(duration_as_micros(Duration::from_secs(1)), 1_000_000);
+        assert_eq!(duration_as_micros(Duration::MAX), i64::MAX);
+    }
+
+    #[test]
+    fn test_huge_window_does_not_overflow() {
+        let sliding_expiration = SlidingExpiration::new(
+            Arc::new(InMemorySessionStore::new()),
+            Duration::MAX,
+            Duration::MAX,
+        );
+
+        let session_entity = SessionEntity::new(
+            "token".to_string(),
+            "trader".to_string(),
+            DateTimeAsMicroseconds::new(2_000_000_000),
+        );
+
+        assert!(sliding_expiration
+            .should_extend(&session_entity, DateTimeAsMicroseconds::new(1_000_000_000)));
+    }
+
+    fn create_expiring_session(now: DateTimeAsMicroseconds) -> SessionEntity {
+        SessionEntity::new(
+            "token".to_string(),
+            "trader".to_string(),
+            now.add(Duration::from_secs(60)),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_extension_keeps_claims_granted_after_read() {
+        let store = Arc::new(InMemorySessionStore::new());
+        let now = DateTimeAsMicroseconds::now();
+
+        let read_session = create_expiring_session(now);
+
+        let mut granted = read_session.clone();
+        granted.set_claim(
+            "withdraw".to_string(),
+            now.add(Duration::from_secs(300)),
+            None,
+        );
+        store.insert(granted);
+
+        let sliding_expiration = SlidingExpiration::new(
+            store.clone(),
+            Duration::from_secs(60 * 10),
+            Duration::from_secs(60 * 60),
+        );
+
+        let extended = sliding_expiration
+            .try_extend(&read_session, None, now)
+            .await
+            .unwrap();
+
+        let saved = store.get_session("token").await.unwrap();
+        assert_eq!(saved.get_expires(), extended.get_expires());
+        assert_eq!(saved.claims.as_ref().unwrap()[0].name, "withdraw");
+    }
+
+    #[tokio::test]
+    async fn test_revoked_session_is_not_written_back() {
+        let store = Arc::new(InMemorySessionStore::new());
+        let now = DateTimeAsMicroseconds::now();
+
+        let sliding_expiration = SlidingExpiration::new(
+            store.clone(),
+            Duration::from_secs(60 * 10),
+            Duration::from_secs(60 * 60),
+        );
+
+        let read_session = create_expiring_session(now);
+
+        // Deleted by revocation after it was read
+        let extended = sliding_expiration
+            .try_extend(&read_session, None, now)
+            .await;
+        assert!(extended.is_none());
+        assert!(store.get_session("token").await.is_none());
+
+        store.insert(read_session.clone());
+
+        let denylist = SessionDenylist::default();
+        denylist.add("token", now);
+
+        let later = now.add(Duration::from_secs(31));
+        let extended = sliding_expiration
+            .try_extend(&read_session, Some(&denylist), later)
+            .await;
+
+        assert!(extended.is_none());
+        assert_eq!(
+            store.get_session("token").await.unwrap().get_expires(),
+            read_session.get_expires()
+        );
+    }
+}
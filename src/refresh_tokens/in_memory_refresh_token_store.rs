@@ -0,0 +1,105 @@
+use std::{collections::HashMap, sync::Mutex};
+
+use service_sdk::rust_extensions::date_time::DateTimeAsMicroseconds;
+
+use super::{RefreshTokenEntity, RefreshTokenStore};
+
+pub struct InMemoryRefreshTokenStore {
+    families: Mutex<HashMap<String, HashMap<String, RefreshTokenEntity>>>,
+}
+
+impl InMemoryRefreshTokenStore {
+    pub fn new() -> Self {
+        Self {
+            families: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl Default for InMemoryRefreshTokenStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait::async_trait]
+impl RefreshTokenStore for InMemoryRefreshTokenStore {
+    async fn get_refresh_token(
+        &self,
+        family_id: &str,
+        refresh_token: &str,
+    ) -> Result<Option<RefreshTokenEntity>, String> {
+        let families = self.families.lock().unwrap();
+
+        let result = families
+            .get(family_id)
+            .and_then(|family| family.get(refresh_token))
+            .cloned();
+
+        Ok(result)
+    }
+
+    async fn consume_refresh_token(
+        &self,
+        family_id: &str,
+        refresh_token: &str,
+    ) -> Result<Option<RefreshTokenEntity>, String> {
+        let mut families = self.families.lock().unwrap();
+
+        let entity = families
+            .get_mut(family_id)
+            .and_then(|family| family.get_mut(refresh_token));
+
+        let Some(entity) = entity else {
+            return Ok(None);
+        };
+
+        if entity.is_consumed() {
+            return Ok(Some(entity.clone()));
+        }
+
+        let consumed = entity.to_consumed(DateTimeAsMicroseconds::now());
+
+        Ok(Some(std::mem::replace(entity, consumed)))
+    }
+
+    async fn get_family(&self, family_id: &str) -> Result<Vec<RefreshTokenEntity>, String> {
+        let families = self.families.lock().unwrap();
+
+        let result = match families.get(family_id) {
+            Some(family) => family.values().cloned().collect(),
+            None => Vec::new(),
+        };
+
+        Ok(result)
+    }
+
+    async fn save_refresh_token(&self, entity: RefreshTokenEntity) -> Result<(), String> {
+        let mut families = self.families.lock().unwrap();
+
+        families
+            .entry(entity.get_family_id().to_string())
+            .or_default()
+            .insert(entity.get_refresh_token().to_string(), entity);
+
+        Ok(())
+    }
+
+    async fn delete_refresh_token(
+        &self,
+        family_id: &str,
+        refresh_token: &str,
+    ) -> Result<(), String> {
+        let mut families = self.families.lock().unwrap();
+
+        if let Some(family) = families.get_mut(family_id) {
+            family.remove(refresh_token);
+
+            if family.is_empty() {
+                families.remove(family_id);
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,12 @@
+mod refresh_token_entity;
+pub use refresh_token_entity::*;
+mod refresh_token_store;
+pub use refresh_token_store::*;
+mod in_memory_refresh_token_store;
+pub use in_memory_refresh_token_store::*;
+#[cfg(feature = "session-writer")]
+mod my_no_sql_refresh_token_store;
+mod token_pair;
+pub use token_pair::*;
+mod refresh_flow;
+pub use refresh_flow::*;
@@ -0,0 +1,72 @@
+use service_sdk::my_no_sql_sdk::data_writer::MyNoSqlDataWriter;
+use service_sdk::rust_extensions::date_time::DateTimeAsMicroseconds;
+
+use super::{RefreshTokenEntity, RefreshTokenStore};
+
+#[async_trait::async_trait]
+impl RefreshTokenStore for MyNoSqlDataWriter<RefreshTokenEntity> {
+    async fn get_refresh_token(
+        &self,
+        family_id: &str,
+        refresh_token: &str,
+    ) -> Result<Option<RefreshTokenEntity>, String> {
+        self.get_entity(family_id, refresh_token, None)
+            .await
+            .map_err(|err| format!("{:?}", err))
+    }
+
+    // Deleting the row is what only one of concurrent calls succeeds with. The consumed token is
+    // written back right after, so a call landing in between finds no token and is rejected
+    // without revoking the family
+    async fn consume_refresh_token(
+        &self,
+        family_id: &str,
+        refresh_token: &str,
+    ) -> Result<Option<RefreshTokenEntity>, String> {
+        let entity = self
+            .delete_row(family_id, refresh_token)
+            .await
+            .map_err(|err| format!("{:?}", err))?;
+
+        let Some(entity) = entity else {
+            return Ok(None);
+        };
+
+        let consumed = if entity.is_consumed() {
+            entity.clone()
+        } else {
+            entity.to_consumed(DateTimeAsMicroseconds::now())
+        };
+
+        self.save_refresh_token(consumed).await?;
+
+        Ok(Some(entity))
+    }
+
+    async fn get_family(&self, family_id: &str) -> Result<Vec<RefreshTokenEntity>, String> {
+        let result = self
+            .get_by_partition_key(family_id, None)
+            .await
+            .map_err(|err| format!("{:?}", err))?;
+
+        Ok(result.unwrap_or_default())
+    }
+
+    async fn save_refresh_token(&self, entity: RefreshTokenEntity) -> Result<(), String> {
+        self.insert_or_replace_entity(&entity)
+            .await
+            .map_err(|err| format!("{:?}", err))
+    }
+
+    async fn delete_refresh_token(
+        &self,
+        family_id: &str,
+        refresh_token: &str,
+    ) -> Result<(), String> {
+        self.delete_row(family_id, refresh_token)
+            .await
+            .map_err(|err| format!("{:?}", err))?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,406 @@
+use std::time::Duration;
+
+use service_sdk::rust_extensions::date_time::DateTimeAsMicroseconds;
+
+use crate::{
+    middlewares::{duration_as_micros, SessionEntity},
+    session_store::SessionWriter,
+    session_tokens::SessionTokenGenerator,
+    ApiHttpResultWithData, ApiResultStatus,
+};
+
+use super::{
+    extract_refresh_token_family_id, RefreshTokenEntity, RefreshTokenStore, TokenPairHttpModel,
+};
+
+pub struct RefreshTokenSettings {
+    pub access_token_lifetime: Duration,
+    pub refresh_token_lifetime: Duration,
+    pub token_generator: SessionTokenGenerator,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SessionOrigin {
+    pub country: Option<String>,
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+// Called by login controller after credentials are verified. Starts a new refresh token family
+pub async fn issue_token_pair(
+    session_writer: &(dyn SessionWriter + Send + Sync),
+    refresh_token_store: &(dyn RefreshTokenStore + Send + Sync),
+    settings: &RefreshTokenSettings,
+    trader_id: String,
+    origin: SessionOrigin,
+) -> Result<ApiHttpResultWithData<TokenPairHttpModel>, String> {
+    let family_id = settings.token_generator.generate();
+
+    let token_pair = write_token_pair(
+        session_writer,
+        refresh_token_store,
+        settings,
+        family_id,
+        trader_id,
+        origin,
+    )
+    .await?;
+
+    Ok(ApiHttpResultWithData {
+        status: ApiResultStatus::Ok,
+        data: Some(token_pair),
+    })
+}
+
+// Called by refresh controller. Rotates the refresh token; presenting an already used token revokes the whole family.
+// Unknown tokens (made up, mistyped or already expired) are rejected without touching the family
+pub async fn refresh_token_pair(
+    session_writer: &(dyn SessionWriter + Send + Sync),
+    refresh_token_store: &(dyn RefreshTokenStore + Send + Sync),
+    settings: &RefreshTokenSettings,
+    refresh_token: &str,
+) -> Result<ApiHttpResultWithData<TokenPairHttpModel>, String> {
+    let family_id = match extract_refresh_token_family_id(refresh_token) {
+        Some(family_id) => family_id,
+        None => return Ok(token_is_invalid()),
+    };
+
+    let entity = refresh_token_store
+        .consume_refresh_token(family_id, refresh_token)
+        .await?;
+
+    let entity = match entity {
+        Some(entity) => entity,
+        None => return Ok(token_is_invalid()),
+    };
+
+    // Token has been used twice
+    if entity.is_consumed() {
+        revoke_refresh_token_family(session_writer, refresh_token_store, family_id).await?;
+        return Ok(token_is_invalid());
+    }
+
+    session_writer.delete_session(&entity.session_token).await?;
+
+    if entity.is_expired(DateTimeAsMicroseconds::now()) {
+        return Ok(token_is_invalid());
+    }
+
+    let origin = SessionOrigin {
+        country: entity.country,
+        ip: entity.ip,
+        user_agent: entity.user_agent,
+    };
+
+    let token_pair = write_token_pair(
+        session_writer,
+        refresh_token_store,
+        settings,
+        family_id.to_string(),
+        entity.trader_id,
+        origin,
+    )
+    .await?;
+
+    Ok(ApiHttpResultWithData {
+        status: ApiResultStatus::Ok,
+        data: Some(token_pair),
+    })
+}
+
+// Deletes refresh tokens of the family together with access sessions issued with them
+pub async fn revoke_refresh_token_family(
+    session_writer: &(dyn SessionWriter + Send + Sync),
+    refresh_token_store: &(dyn RefreshTokenStore + Send + Sync),
+    family_id: &str,
+) -> Result<(), String> {
+    let family = refresh_token_store.get_family(family_id).await?;
+
+    for entity in family {
+        refresh_token_store
+            .delete_refresh_token(family_id, entity.get_refresh_token())
+            .await?;
+
+        session_writer.delete_session(&entity.session_token).await?;
+    }
+
+    Ok(())
+}
+
+async fn write_token_pair(
+    session_writer: &(dyn SessionWriter + Send + Sync),
+    refresh_token_store: &(dyn RefreshTokenStore + Send + Sync),
+    settings: &RefreshTokenSettings,
+    family_id: String,
+    trader_id: String,
+    origin: SessionOrigin,
+) -> Result<TokenPairHttpModel, String> {
+    let now = DateTimeAsMicroseconds::now();
+
+    let access_token_expires = DateTimeAsMicroseconds::new(
+        now.unix_microseconds
+            .saturating_add(duration_as_micros(settings.access_token_lifetime)),
+    );
+
+    let refresh_token_expires = DateTimeAsMicroseconds::new(
+        now.unix_microseconds
+            .saturating_add(duration_as_micros(settings.refresh_token_lifetime)),
+    );
+
+    let mut session_entity = SessionEntity::new(
+        settings.token_generator.generate(),
+        trader_id.clone(),
+        access_token_expires,
+    );
+    session_entity.country = origin.country.clone();
+    session_entity.ip = origin.ip.clone();
+    session_entity.user_agent = origin.user_agent.clone();
+
+    let access_token = session_entity.get_session_token().to_string();
+
+    let mut refresh_entity = RefreshTokenEntity::new(
+        family_id,
+        &settings.token_generator.generate(),
+        trader_id,
+        access_token.clone(),
+        refresh_token_expires,
+    );
+    refresh_entity.country = origin.country;
+    refresh_entity.ip = origin.ip;
+    refresh_entity.user_agent = origin.user_agent;
+
+    let refresh_token = refresh_entity.get_refresh_token().to_string();
+
+    session_writer.save_session(session_entity).await?;
+    refresh_token_store
+        .save_refresh_token(refresh_entity)
+        .await?;
+
+    Ok(TokenPairHttpModel {
+        access_token,
+        access_token_expires: access_token_expires.unix_microseconds,
+        refresh_token,
+        refresh_token_expires: refresh_token_expires.unix_microseconds,
+    })
+}
+
+fn token_is_invalid() -> ApiHttpResultWithData<TokenPairHttpModel> {
+    ApiHttpResultWithData {
+        status: ApiResultStatus::TokenIsInvalid,
+        data: None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use crate::{
+        refresh_tokens::{InMemoryRefreshTokenStore, RefreshTokenEntity, RefreshTokenStore},
+        session_store::{InMemorySessionStore, SessionStore},
+        session_tokens::SessionTokenGenerator,
+        ApiResultStatus,
+    };
+
+    use super::*;
+
+    fn create_settings() -> RefreshTokenSettings {
+        RefreshTokenSettings {
+            access_token_lifetime: Duration::from_secs(60 * 15),
+            refresh_token_lifetime: Duration::from_secs(60 * 60 * 24),
+            token_generator: SessionTokenGenerator::default(),
+        }
+    }
+
+    async fn issue(
+        sessions: &InMemorySessionStore,
+        refresh_tokens: &(dyn RefreshTokenStore + Send + Sync),
+        settings: &RefreshTokenSettings,
+    ) -> TokenPairHttpModel {
+        issue_token_pair(
+            sessions,
+            refresh_tokens,
+            settings,
+            "trader".to_string(),
+            SessionOrigin::default(),
+        )
+        .await
+        .unwrap()
+        .data
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_rotation() {
+        let sessions = InMemorySessionStore::new();
+        let refresh_tokens = InMemoryRefreshTokenStore::new();
+        let settings = create_settings();
+
+        let first = issue(&sessions, &refresh_tokens, &settings).await;
+
+        let result =
+            refresh_token_pair(&sessions, &refresh_tokens, &settings, &first.refresh_token)
+                .await
+                .unwrap();
+
+        assert!(matches!(result.status, ApiResultStatus::Ok));
+        let second = result.data.unwrap();
+
+        assert_ne!(first.refresh_token, second.refresh_token);
+        assert!(sessions.get_session(&first.access_token).await.is_none());
+        assert!(sessions.get_session(&second.access_token).await.is_some());
+        assert_eq!(
+            extract_refresh_token_family_id(&first.refresh_token),
+            extract_refresh_token_family_id(&second.refresh_token)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_reuse_revokes_family() {
+        let sessions = InMemorySessionStore::new();
+        let refresh_tokens = InMemoryRefreshTokenStore::new();
+        let settings = create_settings();
+
+        let first = issue(&sessions, &refresh_tokens, &settings).await;
+
+        let second =
+            refresh_token_pair(&sessions, &refresh_tokens, &settings, &first.refresh_token)
+                .await
+                .unwrap()
+                .data
+                .unwrap();
+
+        let reused =
+            refresh_token_pair(&sessions, &refresh_tokens, &settings, &first.refresh_token)
+                .await
+                .unwrap();
+
+        assert!(matches!(reused.status, ApiResultStatus::TokenIsInvalid));
+        assert!(sessions.get_session(&second.access_token).await.is_none());
+
+        let family_id = extract_refresh_token_family_id(&second.refresh_token).unwrap();
+        assert!(refresh_tokens
+            .get_family(family_id)
+            .await
+            .unwrap()
+            .is_empty());
+
+        let result =
+            refresh_token_pair(&sessions, &refresh_tokens, &settings, &second.refresh_token)
+                .await
+                .unwrap();
+
+        assert!(matches!(result.status, ApiResultStatus::TokenIsInvalid));
+    }
+
+    #[tokio::test]
+    async fn test_unknown_token_does_not_revoke_family() {
+        let sessions = InMemorySessionStore::new();
+        let refresh_tokens = InMemoryRefreshTokenStore::new();
+        let settings = create_settings();
+
+        let first = issue(&sessions, &refresh_tokens, &settings).await;
+        let family_id = extract_refresh_token_family_id(&first.refresh_token).unwrap();
+        let made_up = format!(
+            "{}{}unknown",
+            family_id,
+            RefreshTokenEntity::FAMILY_SEPARATOR
+        );
+
+        let result = refresh_token_pair(&sessions, &refresh_tokens, &settings, &made_up)
+            .await
+            .unwrap();
+
+        assert!(matches!(result.status, ApiResultStatus::TokenIsInvalid));
+        assert!(sessions.get_session(&first.access_token).await.is_some());
+
+        let result =
+            refresh_token_pair(&sessions, &refresh_tokens, &settings, &first.refresh_token)
+                .await
+                .unwrap();
+
+        assert!(matches!(result.status, ApiResultStatus::Ok));
+    }
+
+    #[tokio::test]
+    async fn test_expired_token_deletes_its_session() {
+        let sessions = InMemorySessionStore::new();
+        let refresh_tokens = InMemoryRefreshTokenStore::new();
+        let mut settings = create_settings();
+        settings.refresh_token_lifetime = Duration::ZERO;
+
+        let first = issue(&sessions, &refresh_tokens, &settings).await;
+
+        let result =
+            refresh_token_pair(&sessions, &refresh_tokens, &settings, &first.refresh_token)
+                .await
+                .unwrap();
+
+        assert!(matches!(result.status, ApiResultStatus::TokenIsInvalid));
+        assert!(sessions.get_session(&first.access_token).await.is_none());
+    }
+
+    // Yields before every call so concurrent refreshes interleave between store operations
+    struct YieldingRefreshTokenStore(InMemoryRefreshTokenStore);
+
+    #[async_trait::async_trait]
+    impl RefreshTokenStore for YieldingRefreshTokenStore {
+        async fn get_refresh_token(
+            &self,
+            family_id: &str,
+            refresh_token: &str,
+        ) -> Result<Option<RefreshTokenEntity>, String> {
+            tokio::task::yield_now().await;
+            self.0.get_refresh_token(family_id, refresh_token).await
+        }
+
+        async fn consume_refresh_token(
+            &self,
+            family_id: &str,
+            refresh_token: &str,
+        ) -> Result<Option<RefreshTokenEntity>, String> {
+            tokio::task::yield_now().await;
+            self.0.consume_refresh_token(family_id, refresh_token).await
+        }
+
+        async fn get_family(&self, family_id: &str) -> Result<Vec<RefreshTokenEntity>, String> {
+            tokio::task::yield_now().await;
+            self.0.get_family(family_id).await
+        }
+
+        async fn save_refresh_token(&self, entity: RefreshTokenEntity) -> Result<(), String> {
+            tokio::task::yield_now().await;
+            self.0.save_refresh_token(entity).await
+        }
+
+        async fn delete_refresh_token(
+            &self,
+            family_id: &str,
+            refresh_token: &str,
+        ) -> Result<(), String> {
+            tokio::task::yield_now().await;
+            self.0.delete_refresh_token(family_id, refresh_token).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_refresh_rotates_once() {
+        let sessions = InMemorySessionStore::new();
+        let refresh_tokens = YieldingRefreshTokenStore(InMemoryRefreshTokenStore::new());
+        let settings = create_settings();
+
+        let first = issue(&sessions, &refresh_tokens, &settings).await;
+
+        let (left, right) = tokio::join!(
+            refresh_token_pair(&sessions, &refresh_tokens, &settings, &first.refresh_token),
+            refresh_token_pair(&sessions, &refresh_tokens, &settings, &first.refresh_token),
+        );
+
+        let rotated = [left.unwrap(), right.unwrap()]
+            .into_iter()
+            .filter(|result| matches!(result.status, ApiResultStatus::Ok))
+            .count();
+
+        assert_eq!(rotated, 1);
+    }
+}
@@ -0,0 +1,115 @@
+use serde::{Deserialize, Serialize};
+use service_sdk::{my_no_sql_sdk, rust_extensions::date_time::DateTimeAsMicroseconds};
+
+// PartitionKey is the family id, so a whole family can be read and revoked at once.
+// RowKey is the refresh token itself which has the format {family_id}~{secret}
+#[service_sdk::my_no_sql_sdk::macros::my_no_sql_entity(table_name: "refreshtokens", with_expires: true)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RefreshTokenEntity {
+    pub trader_id: String,
+    // Access session issued together with this refresh token
+    pub session_token: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub country: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ip: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_agent: Option<String>,
+    // Set when the token is used. Used token is kept until it expires,
+    // so presenting it again is told apart from a made up token
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub consumed: Option<i64>,
+}
+
+impl RefreshTokenEntity {
+    pub const FAMILY_SEPARATOR: char = '~';
+
+    pub fn new(
+        family_id: String,
+        secret: &str,
+        trader_id: String,
+        session_token: String,
+        expires: DateTimeAsMicroseconds,
+    ) -> Self {
+        let row_key = format!("{}{}{}", family_id, Self::FAMILY_SEPARATOR, secret);
+
+        Self {
+            partition_key: family_id,
+            row_key,
+            time_stamp: Default::default(),
+            expires: expires.into(),
+            trader_id,
+            session_token,
+            country: None,
+            ip: None,
+            user_agent: None,
+            consumed: None,
+        }
+    }
+
+    pub fn get_refresh_token(&self) -> &str {
+        &self.row_key
+    }
+
+    pub fn get_family_id(&self) -> &str {
+        &self.partition_key
+    }
+
+    pub fn get_expires(&self) -> Option<DateTimeAsMicroseconds> {
+        let expires: DateTimeAsMicroseconds = self.expires.into();
+
+        if expires.unix_microseconds <= 0 {
+            return None;
+        }
+
+        Some(expires)
+    }
+
+    pub fn is_consumed(&self) -> bool {
+        self.consumed.is_some()
+    }
+
+    pub fn to_consumed(&self, now: DateTimeAsMicroseconds) -> Self {
+        let mut result = self.clone();
+        result.consumed = Some(now.unix_microseconds);
+        result
+    }
+
+    pub fn is_expired(&self, now: DateTimeAsMicroseconds) -> bool {
+        match self.get_expires() {
+            Some(expires) => expires.unix_microseconds <= now.unix_microseconds,
+            None => false,
+        }
+    }
+}
+
+pub fn extract_refresh_token_family_id(refresh_token: &str) -> Option<&str> {
+    let (family_id, secret) = refresh_token.split_once(RefreshTokenEntity::FAMILY_SEPARATOR)?;
+
+    if family_id.is_empty() || secret.is_empty() {
+        return None;
+    }
+
+    Some(family_id)
+}
+
+#[cfg(test)]
+mod test {
+    use super::extract_refresh_token_family_id;
+
+    #[test]
+    fn test_extract_family_id() {
+        assert_eq!(
+            Some("family"),
+            extract_refresh_token_family_id("family~secret")
+        );
+        assert_eq!(
+            Some("v1.family"),
+            extract_refresh_token_family_id("v1.family~v1.secret")
+        );
+        assert_eq!(None, extract_refresh_token_family_id("familysecret"));
+        assert_eq!(None, extract_refresh_token_family_id("~secret"));
+        assert_eq!(None, extract_refresh_token_family_id("family~"));
+    }
+}
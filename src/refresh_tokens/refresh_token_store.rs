@@ -0,0 +1,29 @@
+use super::RefreshTokenEntity;
+
+#[async_trait::async_trait]
+pub trait RefreshTokenStore {
+    async fn get_refresh_token(
+        &self,
+        family_id: &str,
+        refresh_token: &str,
+    ) -> Result<Option<RefreshTokenEntity>, String>;
+
+    // Marks the token as consumed and returns it as it was. Of concurrent calls with the same token
+    // only one gets it unconsumed, which is what makes a refresh token single use.
+    // The others and every later call get the consumed token until it expires
+    async fn consume_refresh_token(
+        &self,
+        family_id: &str,
+        refresh_token: &str,
+    ) -> Result<Option<RefreshTokenEntity>, String>;
+
+    async fn get_family(&self, family_id: &str) -> Result<Vec<RefreshTokenEntity>, String>;
+
+    async fn save_refresh_token(&self, entity: RefreshTokenEntity) -> Result<(), String>;
+
+    async fn delete_refresh_token(
+        &self,
+        family_id: &str,
+        refresh_token: &str,
+    ) -> Result<(), String>;
+}
@@ -0,0 +1,15 @@
+use serde::Serialize;
+use service_sdk::my_http_server;
+use service_sdk::my_http_server::macros::MyHttpObjectStructure;
+
+#[derive(Serialize, Debug, MyHttpObjectStructure)]
+pub struct TokenPairHttpModel {
+    #[serde(rename = "accessToken")]
+    pub access_token: String,
+    #[serde(rename = "accessTokenExpires")]
+    pub access_token_expires: i64,
+    #[serde(rename = "refreshToken")]
+    pub refresh_token: String,
+    #[serde(rename = "refreshTokenExpires")]
+    pub refresh_token_expires: i64,
+}
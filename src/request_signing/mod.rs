@@ -0,0 +1,8 @@
+mod signing_key_store;
+pub use signing_key_store::*;
+mod request_signature;
+pub use request_signature::*;
+mod nonce_cache;
+pub use nonce_cache::*;
+mod request_signing_middleware;
+pub use request_signing_middleware::*;
@@ -0,0 +1,111 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap},
+    sync::Mutex,
+};
+
+// (key id, nonce)
+type NonceKey = (String, String);
+
+// Remembers nonces while their timestamps are inside the accepted clock skew window.
+// Nonces are kept in memory of this instance: a replay sent to another replica is accepted
+// unless requests of a key id are always routed to the same instance
+pub struct NonceCache {
+    nonces: Mutex<Nonces>,
+}
+
+struct Nonces {
+    keep_until: HashMap<NonceKey, i64>,
+    // Earliest expiring first, so expired nonces are pruned without scanning all of them
+    expiration_queue: BinaryHeap<Reverse<(i64, NonceKey)>>,
+}
+
+impl Nonces {
+    fn prune(&mut self, now: i64) {
+        while let Some(Reverse((until, _))) = self.expiration_queue.peek() {
+            if *until > now {
+                break;
+            }
+
+            let Some(Reverse((until, key))) = self.expiration_queue.pop() else {
+                break;
+            };
+
+            // The nonce could be registered again since then
+            if self.keep_until.get(&key) == Some(&until) {
+                self.keep_until.remove(&key);
+            }
+        }
+    }
+}
+
+impl NonceCache {
+    pub fn new() -> Self {
+        Self {
+            nonces: Mutex::new(Nonces {
+                keep_until: HashMap::new(),
+                expiration_queue: BinaryHeap::new(),
+            }),
+        }
+    }
+
+    // Returns false if nonce was already used
+    pub fn try_register(&self, key_id: &str, nonce: &str, keep_until: i64, now: i64) -> bool {
+        let mut nonces = self.nonces.lock().unwrap();
+
+        nonces.prune(now);
+
+        let key = (key_id.to_string(), nonce.to_string());
+
+        if nonces.keep_until.contains_key(&key) {
+            return false;
+        }
+
+        nonces.keep_until.insert(key.clone(), keep_until);
+        nonces.expiration_queue.push(Reverse((keep_until, key)));
+
+        true
+    }
+}
+
+impl Default for NonceCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::NonceCache;
+
+    #[test]
+    fn test_nonce_replay() {
+        let cache = NonceCache::new();
+
+        assert!(cache.try_register("partner", "n-1", 100, 0));
+        assert!(!cache.try_register("partner", "n-1", 100, 50));
+        assert!(cache.try_register("other-partner", "n-1", 100, 50));
+        assert!(cache.try_register("partner", "n-1", 200, 101));
+    }
+
+    #[test]
+    fn test_separator_in_key_id_does_not_collide() {
+        let cache = NonceCache::new();
+
+        assert!(cache.try_register("a:b", "c", 100, 0));
+        assert!(cache.try_register("a", "b:c", 100, 0));
+    }
+
+    #[test]
+    fn test_expired_nonces_are_pruned() {
+        let cache = NonceCache::new();
+
+        assert!(cache.try_register("partner", "n-1", 300, 0));
+        assert!(cache.try_register("partner", "n-2", 100, 0));
+        assert!(cache.try_register("partner", "n-3", 200, 150));
+
+        let nonces = cache.nonces.lock().unwrap();
+        assert_eq!(nonces.keep_until.len(), 2);
+        assert_eq!(nonces.expiration_queue.len(), 2);
+    }
+}
@@ -0,0 +1,185 @@
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+
+pub const SIGNATURE_KEY_ID_HEADER: &str = "x-signature-key-id";
+pub const SIGNATURE_TIMESTAMP_HEADER: &str = "x-signature-timestamp";
+pub const SIGNATURE_NONCE_HEADER: &str = "x-signature-nonce";
+pub const SIGNATURE_HEADER: &str = "x-signature";
+
+pub struct RequestToSign<'s> {
+    pub method: &'s str,
+    pub path: &'s str,
+    // Raw query string without '?'
+    pub query: Option<&'s str>,
+    // Unix seconds
+    pub timestamp: i64,
+    pub nonce: &'s str,
+    pub body: &'s [u8],
+}
+
+impl<'s> RequestToSign<'s> {
+    // {METHOD}\n{path}\n{normalized query}\n{timestamp}\n{nonce}\n{hex(sha256(body))}
+    pub fn get_string_to_sign(&self) -> String {
+        format!(
+            "{}\n{}\n{}\n{}\n{}\n{}",
+            self.method.to_uppercase(),
+            self.path,
+            normalize_query(self.query.unwrap_or_default()),
+            self.timestamp,
+            self.nonce,
+            hex::encode(Sha256::digest(self.body))
+        )
+    }
+
+    // Returns hex encoded HMAC-SHA256
+    pub fn sign(&self, secret: &[u8]) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret).unwrap();
+        mac.update(self.get_string_to_sign().as_bytes());
+        hex::encode(mac.finalize().into_bytes())
+    }
+
+    pub fn verify(&self, secret: &[u8], signature: &str) -> bool {
+        let signature = match hex::decode(signature.trim()) {
+            Ok(signature) => signature,
+            Err(_) => return false,
+        };
+
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret).unwrap();
+        mac.update(self.get_string_to_sign().as_bytes());
+        mac.verify_slice(&signature).is_ok()
+    }
+}
+
+// Parameters are percent-decoded, encoded again leaving only RFC 3986 unreserved characters as is
+// and sorted, so the signature does not depend on how the client encoded or ordered them
+pub fn normalize_query(query: &str) -> String {
+    let mut params: Vec<(String, String)> = query
+        .split('&')
+        .filter(|param| !param.is_empty())
+        .map(|param| {
+            let (name, value) = param.split_once('=').unwrap_or((param, ""));
+            (encode_query_component(name), encode_query_component(value))
+        })
+        .collect();
+
+    params.sort();
+
+    let params: Vec<String> = params
+        .into_iter()
+        .map(|(name, value)| format!("{}={}", name, value))
+        .collect();
+
+    params.join("&")
+}
+
+fn encode_query_component(src: &str) -> String {
+    let mut result = String::with_capacity(src.len());
+
+    for b in decode_query_component(src) {
+        if b.is_ascii_alphanumeric() || matches!(b, b'-' | b'.' | b'_' | b'~') {
+            result.push(b as char);
+        } else {
+            result.push_str(&format!("%{:02X}", b));
+        }
+    }
+
+    result
+}
+
+fn decode_query_component(src: &str) -> Vec<u8> {
+    let src = src.as_bytes();
+    let mut result = Vec::with_capacity(src.len());
+
+    let mut i = 0;
+    while i < src.len() {
+        if src[i] == b'+' {
+            result.push(b' ');
+            i += 1;
+            continue;
+        }
+
+        if src[i] == b'%' && i + 3 <= src.len() {
+            if let Ok(decoded) = hex::decode(&src[i + 1..i + 3]) {
+                result.extend_from_slice(&decoded);
+                i += 3;
+                continue;
+            }
+        }
+
+        result.push(src[i]);
+        i += 1;
+    }
+
+    result
+}
+
+pub fn get_signature_headers_description() -> String {
+    format!(
+        "Signed endpoints require headers: {} - partner key id; {} - unix seconds; {} - unique per request; {} - hex HMAC-SHA256 of \"METHOD\\npath\\nquery\\ntimestamp\\nnonce\\nhex(sha256(body))\" where query parameters are percent-encoded (RFC 3986) and sorted",
+        SIGNATURE_KEY_ID_HEADER, SIGNATURE_TIMESTAMP_HEADER, SIGNATURE_NONCE_HEADER, SIGNATURE_HEADER
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn request(body: &[u8]) -> RequestToSign<'_> {
+        RequestToSign {
+            method: "post",
+            path: "/api/payouts",
+            query: Some("currency=USD&account=1"),
+            timestamp: 1700000000,
+            nonce: "n-1",
+            body,
+        }
+    }
+
+    #[test]
+    fn test_string_to_sign() {
+        assert_eq!(
+            "POST\n/api/payouts\naccount=1&currency=USD\n1700000000\nn-1\ne3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+            request(b"").get_string_to_sign()
+        );
+    }
+
+    #[test]
+    fn test_sign_and_verify() {
+        let secret = b"partner-secret";
+        let signature = request(b"{\"amount\":10}").sign(secret);
+
+        assert!(request(b"{\"amount\":10}").verify(secret, &signature));
+        assert!(!request(b"{\"amount\":11}").verify(secret, &signature));
+        assert!(!request(b"{\"amount\":10}").verify(b"other-secret", &signature));
+        assert!(!request(b"{\"amount\":10}").verify(secret, "not-hex"));
+    }
+
+    #[test]
+    fn test_changed_query_fails_verification() {
+        let secret = b"partner-secret";
+        let signature = request(b"").sign(secret);
+
+        let mut reordered = request(b"");
+        reordered.query = Some("account=1&currency=USD");
+        assert!(reordered.verify(secret, &signature));
+
+        let mut changed = request(b"");
+        changed.query = Some("currency=USD&account=2");
+        assert!(!changed.verify(secret, &signature));
+
+        let mut removed = request(b"");
+        removed.query = None;
+        assert!(!removed.verify(secret, &signature));
+    }
+
+    #[test]
+    fn test_normalize_query() {
+        assert_eq!("", normalize_query(""));
+        assert_eq!("a=1&b=", normalize_query("b&a=1"));
+        assert_eq!(
+            "email=a%2Bb%40c.com&q=a%20b",
+            normalize_query("q=a+b&email=a%2bb@c.com")
+        );
+        assert_eq!("q=%25zz", normalize_query("q=%zz"));
+    }
+}
@@ -0,0 +1,140 @@
+use my_http_server::*;
+use service_sdk::my_http_server;
+use service_sdk::rust_extensions::date_time::DateTimeAsMicroseconds;
+use std::{sync::Arc, time::Duration};
+
+use my_http_server::controllers::documentation::{out_results::HttpResult, DataTypeProvider};
+
+use crate::{middlewares::get_header, ApiHttpResult, ApiResultStatus};
+
+use super::{
+    get_signature_headers_description, NonceCache, RequestToSign, SigningKeyStore,
+    SIGNATURE_HEADER, SIGNATURE_KEY_ID_HEADER, SIGNATURE_NONCE_HEADER, SIGNATURE_TIMESTAMP_HEADER,
+};
+
+// Verifies HMAC signature of requests to the protected paths. Other requests are passed through
+pub struct RequestSigningMiddleware {
+    signing_keys_store: Arc<dyn SigningKeyStore + Send + Sync + 'static>,
+    protected_path_prefixes: Vec<String>,
+    max_clock_skew: Duration,
+    nonce_cache: NonceCache,
+}
+
+impl RequestSigningMiddleware {
+    pub fn new(signing_keys_store: Arc<dyn SigningKeyStore + Send + Sync + 'static>) -> Self {
+        Self {
+            signing_keys_store,
+            protected_path_prefixes: Vec::new(),
+            max_clock_skew: Duration::from_secs(60 * 5),
+            nonce_cache: NonceCache::new(),
+        }
+    }
+
+    pub fn protect_path_prefix(mut self, path_prefix: impl Into<String>) -> Self {
+        self.protected_path_prefixes.push(path_prefix.into());
+        self
+    }
+
+    pub fn with_max_clock_skew(mut self, max_clock_skew: Duration) -> Self {
+        self.max_clock_skew = max_clock_skew;
+        self
+    }
+
+    // Add to documentation of the protected routes
+    pub fn get_signature_fail_result_type() -> HttpResult {
+        HttpResult {
+            http_code: ApiResultStatus::SignatureIsInvalid.get_status_code(),
+            nullable: false,
+            description: format!(
+                "Request signature is invalid. Status: {:?}. {}",
+                ApiResultStatus::SignatureIsInvalid,
+                get_signature_headers_description()
+            ),
+            data_type: ApiHttpResult::get_data_type(),
+        }
+    }
+
+    fn is_protected(&self, path: &str) -> bool {
+        self.protected_path_prefixes
+            .iter()
+            .any(|prefix| path.starts_with(prefix.as_str()))
+    }
+
+    async fn verify(&self, ctx: &mut HttpContext) -> Result<(), HttpFailResult> {
+        let key_id = get_signature_header(ctx, SIGNATURE_KEY_ID_HEADER)?;
+        let nonce = get_signature_header(ctx, SIGNATURE_NONCE_HEADER)?;
+        let signature = get_signature_header(ctx, SIGNATURE_HEADER)?;
+        let timestamp: i64 = get_signature_header(ctx, SIGNATURE_TIMESTAMP_HEADER)?
+            .parse()
+            .map_err(|_| signature_is_invalid())?;
+
+        let now = DateTimeAsMicroseconds::now().unix_microseconds / 1_000_000;
+        let max_clock_skew = self.max_clock_skew.as_secs() as i64;
+
+        if (now - timestamp).abs() > max_clock_skew {
+            return Err(signature_is_invalid());
+        }
+
+        let secret = self
+            .signing_keys_store
+            .get_signing_secret(&key_id)
+            .await
+            .ok_or_else(signature_is_invalid)?;
+
+        let method = ctx.request.method.to_string();
+        let path = ctx.request.http_path.as_str().to_string();
+        let query = ctx.request.get_uri().query().map(|query| query.to_string());
+        let body = ctx.request.get_body().await?;
+
+        let request_to_sign = RequestToSign {
+            method: &method,
+            path: &path,
+            query: query.as_deref(),
+            timestamp,
+            nonce: &nonce,
+            body: body.as_slice(),
+        };
+
+        if !request_to_sign.verify(&secret, &signature) {
+            return Err(signature_is_invalid());
+        }
+
+        // Nonce is registered only for correctly signed requests so nobody can burn partner's nonces
+        if !self
+            .nonce_cache
+            .try_register(&key_id, &nonce, timestamp + max_clock_skew, now)
+        {
+            return Err(signature_is_invalid());
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl HttpServerMiddleware for RequestSigningMiddleware {
+    async fn handle_request(
+        &self,
+        ctx: &mut HttpContext,
+    ) -> Option<Result<HttpOkResult, HttpFailResult>> {
+        if !self.is_protected(ctx.request.http_path.as_str()) {
+            return None;
+        }
+
+        match self.verify(ctx).await {
+            Ok(()) => None,
+            Err(err) => Some(Err(err)),
+        }
+    }
+}
+
+fn get_signature_header(ctx: &HttpContext, name: &str) -> Result<String, HttpFailResult> {
+    match get_header(ctx, name) {
+        Ok(Some(value)) if !value.trim().is_empty() => Ok(value.trim().to_string()),
+        _ => Err(signature_is_invalid()),
+    }
+}
+
+fn signature_is_invalid() -> HttpFailResult {
+    ApiResultStatus::SignatureIsInvalid.into()
+}
@@ -0,0 +1,42 @@
+use std::{collections::HashMap, sync::Mutex};
+
+#[async_trait::async_trait]
+pub trait SigningKeyStore {
+    async fn get_signing_secret(&self, key_id: &str) -> Option<Vec<u8>>;
+}
+
+pub struct InMemorySigningKeyStore {
+    secrets: Mutex<HashMap<String, Vec<u8>>>,
+}
+
+impl InMemorySigningKeyStore {
+    pub fn new() -> Self {
+        Self {
+            secrets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn insert(&self, key_id: impl Into<String>, secret: Vec<u8>) {
+        let mut secrets = self.secrets.lock().unwrap();
+        secrets.insert(key_id.into(), secret);
+    }
+
+    pub fn remove(&self, key_id: &str) {
+        let mut secrets = self.secrets.lock().unwrap();
+        secrets.remove(key_id);
+    }
+}
+
+impl Default for InMemorySigningKeyStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait::async_trait]
+impl SigningKeyStore for InMemorySigningKeyStore {
+    async fn get_signing_secret(&self, key_id: &str) -> Option<Vec<u8>> {
+        let secrets = self.secrets.lock().unwrap();
+        secrets.get(key_id).cloned()
+    }
+}
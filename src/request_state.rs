@@ -0,0 +1,33 @@
+use std::sync::Arc;
+
+use crate::{
+    error_responses::NegotiatedErrorResponse, middlewares::AuthFailReason, task_slot::TaskSlot,
+};
+
+// What middlewares recorded about the request served by the current task, for code which is
+// called without HttpContext. ErrorResponseMiddleware starts a new state for every request,
+// so a task keeps one state whatever the number of requests it serves
+#[derive(Clone, Default)]
+pub(crate) struct RequestState {
+    pub negotiated_error_response: Option<Arc<NegotiatedErrorResponse>>,
+    pub auth_fail_reason: Option<AuthFailReason>,
+}
+
+static REQUEST_STATES: TaskSlot<RequestState> = TaskSlot::new();
+
+pub(crate) fn begin_request_state(negotiated_error_response: Arc<NegotiatedErrorResponse>) {
+    REQUEST_STATES.set(RequestState {
+        negotiated_error_response: Some(negotiated_error_response),
+        auth_fail_reason: None,
+    });
+}
+
+pub(crate) fn get_request_state() -> Option<RequestState> {
+    REQUEST_STATES.get()
+}
+
+pub(crate) fn update_request_state<TResult>(
+    update: impl FnOnce(&mut RequestState) -> TResult,
+) -> Option<TResult> {
+    REQUEST_STATES.update(update)
+}
@@ -0,0 +1,146 @@
+use crate::{ApiResultStatus, ResultStatusRange, ServiceResultStatus};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResultStatusRegistryError {
+    RangeCollision {
+        service: &'static str,
+        range: ResultStatusRange,
+        collides_with: &'static str,
+    },
+    ValueOutOfRange {
+        service: &'static str,
+        range: ResultStatusRange,
+        value: i16,
+    },
+}
+
+struct RegisteredRange {
+    service: &'static str,
+    range: ResultStatusRange,
+}
+
+// Is built once at startup to make sure services do not reuse each other's status values
+pub struct ResultStatusRegistry {
+    ranges: Vec<RegisteredRange>,
+}
+
+impl ResultStatusRegistry {
+    pub fn new() -> Self {
+        let result = Self { ranges: Vec::new() };
+
+        result
+            .register::<ApiResultStatus>()
+            .expect("Shared ApiResultStatus range must be valid")
+    }
+
+    pub fn register<TStatus: ServiceResultStatus>(self) -> Result<Self, ResultStatusRegistryError> {
+        let values = TStatus::get_all().iter().map(|status| status.get_value());
+        self.register_range(TStatus::SERVICE_NAME, TStatus::RANGE, values)
+    }
+
+    pub fn register_range(
+        mut self,
+        service: &'static str,
+        range: ResultStatusRange,
+        values: impl Iterator<Item = i16>,
+    ) -> Result<Self, ResultStatusRegistryError> {
+        if let Some(registered) = self.ranges.iter().find(|itm| itm.range.overlaps(&range)) {
+            return Err(ResultStatusRegistryError::RangeCollision {
+                service,
+                range,
+                collides_with: registered.service,
+            });
+        }
+
+        for value in values {
+            if !range.contains(value) {
+                return Err(ResultStatusRegistryError::ValueOutOfRange {
+                    service,
+                    range,
+                    value,
+                });
+            }
+        }
+
+        self.ranges.push(RegisteredRange { service, range });
+        Ok(self)
+    }
+
+    pub fn find_service(&self, value: i16) -> Option<&'static str> {
+        self.ranges
+            .iter()
+            .find(|itm| itm.range.contains(value))
+            .map(|itm| itm.service)
+    }
+}
+
+impl Default for ResultStatusRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_shared_range_is_registered() {
+        let registry = ResultStatusRegistry::new();
+
+        assert_eq!(registry.find_service(-17), Some("shared"));
+        assert_eq!(registry.find_service(-1001), None);
+    }
+
+    #[test]
+    fn test_range_collision() {
+        let registry = ResultStatusRegistry::new()
+            .register_range(
+                "kyc",
+                ResultStatusRange::new(-1999, -1000),
+                [-1000, -1001].into_iter(),
+            )
+            .unwrap();
+
+        assert_eq!(registry.find_service(-1001), Some("kyc"));
+
+        let err = registry
+            .register_range(
+                "cards",
+                ResultStatusRange::new(-2999, -1999),
+                [].into_iter(),
+            )
+            .err()
+            .unwrap();
+
+        assert_eq!(
+            err,
+            ResultStatusRegistryError::RangeCollision {
+                service: "cards",
+                range: ResultStatusRange::new(-2999, -1999),
+                collides_with: "kyc",
+            }
+        );
+    }
+
+    #[test]
+    fn test_value_out_of_range() {
+        let err = ResultStatusRegistry::new()
+            .register_range(
+                "staking",
+                ResultStatusRange::new(-3999, -3000),
+                [-3001, -4000].into_iter(),
+            )
+            .err()
+            .unwrap();
+
+        assert_eq!(
+            err,
+            ResultStatusRegistryError::ValueOutOfRange {
+                service: "staking",
+                range: ResultStatusRange::new(-3999, -3000),
+                value: -4000,
+            }
+        );
+    }
+}
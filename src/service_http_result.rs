@@ -0,0 +1,69 @@
+use serde::Serialize;
+use service_sdk::my_http_server;
+use service_sdk::my_http_server::controllers::documentation::DataTypeProvider;
+use service_sdk::my_http_server::macros::MyHttpObjectStructure;
+use service_sdk::my_http_server::*;
+
+use crate::error_responses::{ErrorResponse, ProblemDetails};
+use crate::{into_http_fail_result, ResultStatus};
+
+// Same as ApiHttpResult, but for statuses of a ServiceResultStatus enum
+#[derive(Serialize, MyHttpObjectStructure)]
+pub struct ServiceHttpResult<TStatus: ResultStatus> {
+    pub status: TStatus,
+}
+
+impl<TStatus: ResultStatus> ErrorResponse for ServiceHttpResult<TStatus> {
+    fn get_result_status(&self) -> i16 {
+        self.status.get_value()
+    }
+
+    fn into_legacy_fail_result(self, message: Option<&str>) -> HttpFailResult {
+        into_http_fail_result(&self, &self.status, message)
+    }
+
+    fn into_problem_details(self) -> ProblemDetails {
+        ProblemDetails::new(&self.status)
+    }
+}
+
+impl<TStatus: ResultStatus> Into<HttpFailResult> for ServiceHttpResult<TStatus> {
+    fn into(self) -> HttpFailResult {
+        self.into_fail_result()
+    }
+}
+
+#[derive(Serialize, MyHttpObjectStructure)]
+pub struct ServiceHttpResultWithData<TData: Serialize + DataTypeProvider, TStatus: ResultStatus> {
+    pub status: TStatus,
+    pub data: Option<TData>,
+}
+
+impl<TData: Serialize + DataTypeProvider, TStatus: ResultStatus> ErrorResponse
+    for ServiceHttpResultWithData<TData, TStatus>
+{
+    fn get_result_status(&self) -> i16 {
+        self.status.get_value()
+    }
+
+    fn into_legacy_fail_result(self, message: Option<&str>) -> HttpFailResult {
+        into_http_fail_result(&self, &self.status, message)
+    }
+
+    fn into_problem_details(self) -> ProblemDetails {
+        let result = ProblemDetails::new(&self.status);
+
+        match &self.data {
+            Some(data) => result.with_data(data),
+            None => result,
+        }
+    }
+}
+
+impl<TData: Serialize + DataTypeProvider, TStatus: ResultStatus> Into<HttpFailResult>
+    for ServiceHttpResultWithData<TData, TStatus>
+{
+    fn into(self) -> HttpFailResult {
+        self.into_fail_result()
+    }
+}
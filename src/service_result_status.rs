@@ -0,0 +1,40 @@
+use serde::Serialize;
+use service_sdk::my_http_server::controllers::documentation::DataTypeProvider;
+
+// Anything that can be rendered as `{"status": n}` through ServiceHttpResult/ServiceHttpResultWithData
+pub trait ResultStatus: Serialize + DataTypeProvider + Copy + Send + Sync + 'static {
+    fn get_value(&self) -> i16;
+    fn get_description(&self) -> &'static str;
+    fn get_status_code(&self) -> u16;
+    fn write_to_telemetry(&self) -> bool;
+}
+
+// Implemented by service specific enums (KYC, cards, staking...) which own a reserved range of values.
+// The shared ApiResultStatus owns -999..=0.
+pub trait ServiceResultStatus: ResultStatus {
+    const SERVICE_NAME: &'static str;
+    const RANGE: ResultStatusRange;
+
+    fn get_all() -> &'static [Self];
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResultStatusRange {
+    pub from: i16,
+    pub to: i16,
+}
+
+impl ResultStatusRange {
+    pub const fn new(from: i16, to: i16) -> Self {
+        assert!(from <= to, "ResultStatusRange: from must be <= to");
+        Self { from, to }
+    }
+
+    pub fn contains(&self, value: i16) -> bool {
+        value >= self.from && value <= self.to
+    }
+
+    pub fn overlaps(&self, other: &ResultStatusRange) -> bool {
+        self.from <= other.to && other.from <= self.to
+    }
+}
@@ -0,0 +1,140 @@
+use std::collections::{HashMap, VecDeque};
+
+struct CacheItem<TValue> {
+    value: TValue,
+    expires: i64,
+    seq: u64,
+}
+
+// Not thread safe, callers keep it behind a Mutex. When full, the oldest inserted item is evicted
+pub struct BoundedTtlCache<TValue: Clone> {
+    max_items: usize,
+    items: HashMap<String, CacheItem<TValue>>,
+    insert_order: VecDeque<(String, u64)>,
+    next_seq: u64,
+}
+
+impl<TValue: Clone> BoundedTtlCache<TValue> {
+    pub fn new(max_items: usize) -> Self {
+        Self {
+            max_items,
+            items: HashMap::new(),
+            insert_order: VecDeque::new(),
+            next_seq: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn get(&mut self, key: &str, now: i64) -> Option<TValue> {
+        let item = self.items.get(key)?;
+
+        if item.expires <= now {
+            self.items.remove(key);
+            return None;
+        }
+
+        Some(item.value.clone())
+    }
+
+    pub fn insert(&mut self, key: String, value: TValue, expires: i64) {
+        if self.max_items == 0 {
+            return;
+        }
+
+        let seq = self.next_seq;
+        self.next_seq += 1;
+
+        self.insert_order.push_back((key.clone(), seq));
+        self.items.insert(
+            key,
+            CacheItem {
+                value,
+                expires,
+                seq,
+            },
+        );
+
+        while self.items.len() > self.max_items {
+            let (key, seq) = match self.insert_order.pop_front() {
+                Some(item) => item,
+                None => break,
+            };
+
+            if let Some(item) = self.items.get(&key) {
+                if item.seq == seq {
+                    self.items.remove(&key);
+                }
+            }
+        }
+
+        // Keys which were replaced or removed leave stale records behind
+        if self.insert_order.len() > self.max_items * 2 {
+            let items = &self.items;
+            self.insert_order.retain(|(key, seq)| match items.get(key) {
+                Some(item) => item.seq == *seq,
+                None => false,
+            });
+        }
+    }
+
+    pub fn remove(&mut self, key: &str) {
+        self.items.remove(key);
+    }
+
+    pub fn retain(&mut self, predicate: impl Fn(&TValue) -> bool) {
+        self.items.retain(|_, item| predicate(&item.value));
+    }
+
+    pub fn clear(&mut self) {
+        self.items.clear();
+        self.insert_order.clear();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::BoundedTtlCache;
+
+    #[test]
+    fn test_ttl() {
+        let mut cache = BoundedTtlCache::new(10);
+        cache.insert("a".to_string(), 1, 100);
+
+        assert_eq!(Some(1), cache.get("a", 99));
+        assert_eq!(None, cache.get("a", 100));
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_oldest_is_evicted() {
+        let mut cache = BoundedTtlCache::new(2);
+        cache.insert("a".to_string(), 1, 100);
+        cache.insert("b".to_string(), 2, 100);
+        cache.insert("a".to_string(), 3, 100);
+        cache.insert("c".to_string(), 4, 100);
+
+        assert_eq!(2, cache.len());
+        assert_eq!(None, cache.get("b", 0));
+        assert_eq!(Some(3), cache.get("a", 0));
+        assert_eq!(Some(4), cache.get("c", 0));
+    }
+
+    #[test]
+    fn test_insert_order_does_not_grow() {
+        let mut cache = BoundedTtlCache::new(2);
+
+        for i in 0..100 {
+            cache.insert("a".to_string(), i, 100);
+        }
+
+        assert!(cache.insert_order.len() <= 4);
+        assert_eq!(Some(99), cache.get("a", 0));
+    }
+}
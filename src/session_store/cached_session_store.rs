@@ -0,0 +1,272 @@
+use std::{
+    sync::atomic::{AtomicU64, Ordering},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use service_sdk::rust_extensions::date_time::DateTimeAsMicroseconds;
+
+use crate::middlewares::{duration_as_micros, SessionEntity};
+
+use super::{BoundedTtlCache, SessionDenylist, SessionStore, SessionWriter};
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SessionCacheStats {
+    pub hits: u64,
+    pub negative_hits: u64,
+    pub misses: u64,
+    pub items: usize,
+}
+
+// Sits in front of a session store. Unknown tokens are cached too (with a shorter ttl),
+// so bots and stale apps do not hit the store on every request.
+// Sessions are written through it as well (it is a SessionWriter), so a write drops the cached copy
+pub struct CachedSessionStore {
+    inner: Arc<dyn SessionStore + Send + Sync + 'static>,
+    session_writer: Arc<dyn SessionWriter + Send + Sync + 'static>,
+    positive_ttl: Duration,
+    negative_ttl: Duration,
+    cache: Mutex<BoundedTtlCache<Option<Arc<SessionEntity>>>>,
+    hits: AtomicU64,
+    negative_hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl CachedSessionStore {
+    // A revoked session must leave the cache before it leaves the denylist,
+    // otherwise the cached copy is served after the denylist forgot the token.
+    // So positive_ttl is capped by the time the denylist keeps revoked tokens
+    pub fn new(
+        inner: Arc<dyn SessionStore + Send + Sync + 'static>,
+        session_writer: Arc<dyn SessionWriter + Send + Sync + 'static>,
+        session_denylist: &SessionDenylist,
+        max_items: usize,
+        positive_ttl: Duration,
+        negative_ttl: Duration,
+    ) -> Self {
+        Self {
+            inner,
+            session_writer,
+            positive_ttl: positive_ttl.min(session_denylist.get_keep_revoked()),
+            negative_ttl,
+            cache: Mutex::new(BoundedTtlCache::new(max_items)),
+            hits: AtomicU64::new(0),
+            negative_hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    pub fn invalidate(&self, session_token: &str) {
+        let mut cache = self.cache.lock().unwrap();
+        cache.remove(session_token);
+    }
+
+    pub fn invalidate_trader(&self, trader_id: &str) {
+        let mut cache = self.cache.lock().unwrap();
+        cache.retain(|session| match session {
+            Some(session) => session.trader_id != trader_id,
+            None => true,
+        });
+    }
+
+    pub fn clear(&self) {
+        let mut cache = self.cache.lock().unwrap();
+        cache.clear();
+    }
+
+    pub fn get_stats(&self) -> SessionCacheStats {
+        let items = self.cache.lock().unwrap().len();
+
+        SessionCacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            negative_hits: self.negative_hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            items,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl SessionStore for CachedSessionStore {
+    async fn get_session(&self, session_token: &str) -> Option<Arc<SessionEntity>> {
+        let now = DateTimeAsMicroseconds::now().unix_microseconds;
+
+        let cached = {
+            let mut cache = self.cache.lock().unwrap();
+            cache.get(session_token, now)
+        };
+
+        if let Some(cached) = cached {
+            match cached.as_ref() {
+                Some(_) => self.hits.fetch_add(1, Ordering::Relaxed),
+                None => self.negative_hits.fetch_add(1, Ordering::Relaxed),
+            };
+
+            return cached;
+        }
+
+        self.misses.fetch_add(1, Ordering::Relaxed);
+
+        let result = self.inner.get_session(session_token).await;
+
+        let ttl = if result.is_some() {
+            self.positive_ttl
+        } else {
+            self.negative_ttl
+        };
+
+        let mut cache = self.cache.lock().unwrap();
+        cache.insert(
+            session_token.to_string(),
+            result.clone(),
+            now.saturating_add(duration_as_micros(ttl)),
+        );
+
+        result
+    }
+
+    async fn get_trader_sessions(&self, trader_id: &str) -> Option<Vec<Arc<SessionEntity>>> {
+        self.inner.get_trader_sessions(trader_id).await
+    }
+}
+
+// Other instances see the write once the table update reaches their callbacks
+#[async_trait::async_trait]
+impl SessionWriter for CachedSessionStore {
+    async fn save_session(&self, session_entity: SessionEntity) -> Result<(), String> {
+        let session_token = session_entity.get_session_token().to_string();
+        let result = self.session_writer.save_session(session_entity).await;
+        self.invalidate(&session_token);
+        result
+    }
+
+    async fn update_session(
+        &self,
+        session_token: &str,
+        update: &(dyn for<'s> Fn(&'s mut SessionEntity) + Send + Sync),
+    ) -> Result<Option<SessionEntity>, String> {
+        let result = self
+            .session_writer
+            .update_session(session_token, update)
+            .await;
+        self.invalidate(session_token);
+        result
+    }
+
+    async fn delete_session(&self, session_token: &str) -> Result<(), String> {
+        let result = self.session_writer.delete_session(session_token).await;
+        self.invalidate(session_token);
+        result
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{sync::Arc, time::Duration};
+
+    use service_sdk::rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use super::CachedSessionStore;
+    use crate::middlewares::SessionEntity;
+    use crate::session_store::{
+        InMemorySessionStore, SessionDenylist, SessionStore, SessionWriter,
+    };
+
+    fn create_session(session_token: &str) -> SessionEntity {
+        SessionEntity::new(
+            session_token.to_string(),
+            "trader".to_string(),
+            DateTimeAsMicroseconds::now().add(Duration::from_secs(3600)),
+        )
+    }
+
+    fn create_cache(inner: Arc<InMemorySessionStore>) -> CachedSessionStore {
+        CachedSessionStore::new(
+            inner.clone(),
+            inner,
+            &SessionDenylist::new(Duration::from_secs(60)),
+            16,
+            Duration::from_secs(60),
+            Duration::from_secs(10),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_hits_and_misses_are_counted() {
+        let inner = Arc::new(InMemorySessionStore::new());
+        inner.insert(create_session("token"));
+
+        let cache = create_cache(inner.clone());
+
+        assert!(cache.get_session("token").await.is_some());
+        assert!(cache.get_session("token").await.is_some());
+
+        let stats = cache.get_stats();
+        assert_eq!(stats.misses, 1);
+        assert_eq!(stats.hits, 1);
+        assert_eq!(stats.negative_hits, 0);
+        assert_eq!(stats.items, 1);
+    }
+
+    #[tokio::test]
+    async fn test_unknown_token_is_cached() {
+        let inner = Arc::new(InMemorySessionStore::new());
+        let cache = create_cache(inner.clone());
+
+        assert!(cache.get_session("token").await.is_none());
+
+        inner.insert(create_session("token"));
+        assert!(cache.get_session("token").await.is_none());
+
+        let stats = cache.get_stats();
+        assert_eq!(stats.misses, 1);
+        assert_eq!(stats.negative_hits, 1);
+        assert_eq!(stats.hits, 0);
+
+        cache.invalidate("token");
+        assert!(cache.get_session("token").await.is_some());
+        assert_eq!(cache.get_stats().misses, 2);
+    }
+
+    #[tokio::test]
+    async fn test_positive_ttl_is_capped_by_denylist() {
+        let inner = Arc::new(InMemorySessionStore::new());
+        inner.insert(create_session("token"));
+
+        let cache = CachedSessionStore::new(
+            inner.clone(),
+            inner.clone(),
+            &SessionDenylist::new(Duration::ZERO),
+            16,
+            Duration::from_secs(60),
+            Duration::from_secs(10),
+        );
+
+        assert!(cache.get_session("token").await.is_some());
+
+        inner.remove("token");
+        assert!(cache.get_session("token").await.is_none());
+        assert_eq!(cache.get_stats().misses, 2);
+    }
+
+    #[tokio::test]
+    async fn test_write_drops_cached_session() {
+        let inner = Arc::new(InMemorySessionStore::new());
+        inner.insert(create_session("token"));
+
+        let cache = create_cache(inner.clone());
+        assert!(cache.get_session("token").await.unwrap().claims.is_none());
+
+        cache
+            .update_session("token", &|session| {
+                session.claims = Some(vec![]);
+            })
+            .await
+            .unwrap();
+
+        assert!(cache.get_session("token").await.unwrap().claims.is_some());
+
+        cache.delete_session("token").await.unwrap();
+        assert!(cache.get_session("token").await.is_none());
+    }
+}
@@ -0,0 +1,23 @@
+use std::sync::Arc;
+
+use service_sdk::my_no_sql_sdk::reader::MyNoSqlDataReaderCallBacks;
+
+use crate::middlewares::SessionEntity;
+
+use super::CachedSessionStore;
+
+// Subscribe with sessions_reader.assign_callback(cached_session_store.clone()) to drop stale cache items on table updates
+#[async_trait::async_trait]
+impl MyNoSqlDataReaderCallBacks<SessionEntity> for CachedSessionStore {
+    async fn inserted_or_replaced(&self, _partition_key: &str, entities: Vec<Arc<SessionEntity>>) {
+        for entity in entities {
+            self.invalidate(entity.get_session_token());
+        }
+    }
+
+    async fn deleted(&self, _partition_key: &str, entities: Vec<Arc<SessionEntity>>) {
+        for entity in entities {
+            self.invalidate(entity.get_session_token());
+        }
+    }
+}
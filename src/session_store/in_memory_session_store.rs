@@ -0,0 +1,89 @@
+use std::{collections::HashMap, sync::Arc, sync::Mutex};
+
+use crate::middlewares::SessionEntity;
+
+use super::{SessionStore, SessionWriter};
+
+pub struct InMemorySessionStore {
+    sessions: Mutex<HashMap<String, Arc<SessionEntity>>>,
+}
+
+impl InMemorySessionStore {
+    pub fn new() -> Self {
+        Self {
+            sessions: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn insert(&self, session_entity: SessionEntity) {
+        let mut sessions = self.sessions.lock().unwrap();
+        sessions.insert(
+            session_entity.get_session_token().to_string(),
+            Arc::new(session_entity),
+        );
+    }
+
+    pub fn remove(&self, session_token: &str) -> Option<Arc<SessionEntity>> {
+        let mut sessions = self.sessions.lock().unwrap();
+        sessions.remove(session_token)
+    }
+}
+
+impl Default for InMemorySessionStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait::async_trait]
+impl SessionStore for InMemorySessionStore {
+    async fn get_session(&self, session_token: &str) -> Option<Arc<SessionEntity>> {
+        let sessions = self.sessions.lock().unwrap();
+        sessions.get(session_token).cloned()
+    }
+
+    async fn get_trader_sessions(&self, trader_id: &str) -> Option<Vec<Arc<SessionEntity>>> {
+        let sessions = self.sessions.lock().unwrap();
+
+        let result: Vec<_> = sessions
+            .values()
+            .filter(|itm| itm.trader_id == trader_id)
+            .cloned()
+            .collect();
+
+        if result.is_empty() {
+            return None;
+        }
+
+        Some(result)
+    }
+}
+
+#[async_trait::async_trait]
+impl SessionWriter for InMemorySessionStore {
+    async fn save_session(&self, session_entity: SessionEntity) -> Result<(), String> {
+        self.insert(session_entity);
+        Ok(())
+    }
+
+    async fn update_session(
+        &self,
+        session_token: &str,
+        update: &(dyn for<'s> Fn(&'s mut SessionEntity) + Send + Sync),
+    ) -> Result<Option<SessionEntity>, String> {
+        let mut sessions = self.sessions.lock().unwrap();
+
+        let Some(session_entity) = sessions.get_mut(session_token) else {
+            return Ok(None);
+        };
+
+        update(Arc::make_mut(session_entity));
+
+        Ok(Some(session_entity.as_ref().clone()))
+    }
+
+    async fn delete_session(&self, session_token: &str) -> Result<(), String> {
+        self.remove(session_token);
+        Ok(())
+    }
+}
@@ -0,0 +1,28 @@
+mod session_store;
+pub use session_store::*;
+mod session_writer;
+pub use session_writer::*;
+mod session_denylist;
+pub use session_denylist::*;
+mod session_revocation;
+pub use session_revocation::*;
+mod session_limits;
+pub use session_limits::*;
+mod bounded_ttl_cache;
+pub use bounded_ttl_cache::*;
+mod cached_session_store;
+pub use cached_session_store::*;
+mod in_memory_session_store;
+pub use in_memory_session_store::*;
+mod trader_sessions_index;
+pub use trader_sessions_index::*;
+#[cfg(feature = "auth-middleware")]
+mod cached_session_store_callbacks;
+#[cfg(feature = "auth-middleware")]
+mod my_no_sql_session_store;
+#[cfg(feature = "auth-middleware")]
+mod trader_indexed_session_store;
+#[cfg(feature = "auth-middleware")]
+pub use trader_indexed_session_store::*;
+#[cfg(feature = "session-writer")]
+mod my_no_sql_session_writer;
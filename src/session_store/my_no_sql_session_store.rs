@@ -0,0 +1,16 @@
+use std::sync::Arc;
+
+use service_sdk::my_no_sql_sdk::reader::MyNoSqlDataReaderTcp;
+
+use crate::middlewares::SessionEntity;
+
+use super::SessionStore;
+
+// Does not look up sessions of a trader. Use TraderIndexedSessionStore for SessionRevocation and SessionLimits
+#[async_trait::async_trait]
+impl SessionStore for MyNoSqlDataReaderTcp<SessionEntity> {
+    async fn get_session(&self, session_token: &str) -> Option<Arc<SessionEntity>> {
+        self.get_entity(SessionEntity::PARTITION_KEY, session_token)
+            .await
+    }
+}
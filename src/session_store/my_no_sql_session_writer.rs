@@ -0,0 +1,44 @@
+use service_sdk::my_no_sql_sdk::data_writer::MyNoSqlDataWriter;
+
+use crate::middlewares::SessionEntity;
+
+use super::SessionWriter;
+
+#[async_trait::async_trait]
+impl SessionWriter for MyNoSqlDataWriter<SessionEntity> {
+    async fn save_session(&self, session_entity: SessionEntity) -> Result<(), String> {
+        self.insert_or_replace_entity(&session_entity)
+            .await
+            .map_err(|err| format!("{:?}", err))
+    }
+
+    // Re-reads the session from the server, not from the reader cache. The write is not
+    // conditional, so only an update landing between this read and the write is lost
+    async fn update_session(
+        &self,
+        session_token: &str,
+        update: &(dyn for<'s> Fn(&'s mut SessionEntity) + Send + Sync),
+    ) -> Result<Option<SessionEntity>, String> {
+        let session_entity = self
+            .get_entity(SessionEntity::PARTITION_KEY, session_token, None)
+            .await
+            .map_err(|err| format!("{:?}", err))?;
+
+        let Some(mut session_entity) = session_entity else {
+            return Ok(None);
+        };
+
+        update(&mut session_entity);
+        self.save_session(session_entity.clone()).await?;
+
+        Ok(Some(session_entity))
+    }
+
+    async fn delete_session(&self, session_token: &str) -> Result<(), String> {
+        self.delete_row(SessionEntity::PARTITION_KEY, session_token)
+            .await
+            .map_err(|err| format!("{:?}", err))?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,68 @@
+use std::{collections::HashMap, sync::Mutex, time::Duration};
+
+use service_sdk::rust_extensions::date_time::DateTimeAsMicroseconds;
+
+// Keeps revoked tokens until the deletion reaches every MyNoSql reader
+pub struct SessionDenylist {
+    keep_revoked: Duration,
+    revoked: Mutex<HashMap<String, i64>>,
+}
+
+impl SessionDenylist {
+    pub fn new(keep_revoked: Duration) -> Self {
+        Self {
+            keep_revoked,
+            revoked: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn get_keep_revoked(&self) -> Duration {
+        self.keep_revoked
+    }
+
+    pub fn add(&self, session_token: &str, now: DateTimeAsMicroseconds) {
+        let keep_until = now.unix_microseconds + self.keep_revoked.as_micros() as i64;
+
+        let mut revoked = self.revoked.lock().unwrap();
+        revoked.retain(|_, until| *until > now.unix_microseconds);
+        revoked.insert(session_token.to_string(), keep_until);
+    }
+
+    pub fn is_revoked(&self, session_token: &str, now: DateTimeAsMicroseconds) -> bool {
+        let revoked = self.revoked.lock().unwrap();
+
+        match revoked.get(session_token) {
+            Some(until) => *until > now.unix_microseconds,
+            None => false,
+        }
+    }
+}
+
+impl Default for SessionDenylist {
+    fn default() -> Self {
+        Self::new(Duration::from_secs(60 * 5))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use service_sdk::rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use super::SessionDenylist;
+
+    #[test]
+    fn test_revoked_token_is_kept_for_configured_time() {
+        let denylist = SessionDenylist::new(Duration::from_secs(10));
+
+        let now = DateTimeAsMicroseconds::new(1_000_000_000);
+        denylist.add("token", now);
+
+        assert!(denylist.is_revoked("token", now));
+        assert!(!denylist.is_revoked("other-token", now));
+
+        let later = DateTimeAsMicroseconds::new(now.unix_microseconds + 11_000_000);
+        assert!(!denylist.is_revoked("token", later));
+    }
+}
@@ -0,0 +1,291 @@
+use service_sdk::rust_extensions::date_time::DateTimeAsMicroseconds;
+
+use crate::ApiResultStatus;
+
+use super::SessionRevocation;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeviceType {
+    Mobile,
+    Desktop,
+    Unknown,
+}
+
+impl DeviceType {
+    pub fn detect(user_agent: Option<&str>) -> Self {
+        let user_agent = match user_agent {
+            Some(user_agent) => user_agent.to_lowercase(),
+            None => return DeviceType::Unknown,
+        };
+
+        const MOBILE_MARKERS: [&str; 6] =
+            ["android", "iphone", "ipad", "mobile", "okhttp", "cfnetwork"];
+
+        if MOBILE_MARKERS
+            .iter()
+            .any(|marker| user_agent.contains(marker))
+        {
+            return DeviceType::Mobile;
+        }
+
+        const DESKTOP_MARKERS: [&str; 4] = ["windows", "macintosh", "linux", "cros"];
+
+        if DESKTOP_MARKERS
+            .iter()
+            .any(|marker| user_agent.contains(marker))
+        {
+            return DeviceType::Desktop;
+        }
+
+        DeviceType::Unknown
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionLimitAction {
+    EvictOldest,
+    Refuse,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct SessionLimitPolicy {
+    pub max_sessions_per_trader: Option<usize>,
+    pub max_sessions_per_device_type: Option<usize>,
+    pub on_limit_reached: SessionLimitAction,
+}
+
+#[derive(Debug)]
+pub enum SessionLimitError {
+    LimitReached,
+    StoreError(String),
+}
+
+impl SessionLimitError {
+    pub fn get_api_result_status(&self) -> Option<ApiResultStatus> {
+        match self {
+            SessionLimitError::LimitReached => Some(ApiResultStatus::SessionsLimitReached),
+            SessionLimitError::StoreError(_) => None,
+        }
+    }
+}
+
+pub struct ActiveSession<'s> {
+    pub session_token: &'s str,
+    pub created: i64,
+    pub device_type: DeviceType,
+}
+
+impl SessionLimitPolicy {
+    // Must be called before a new session is written. Returns amount of evicted sessions.
+    // Counting and writing are not atomic: concurrent logins of a trader (on this or other
+    // instances) can all see room for one more session, so the limit can be exceeded by
+    // the amount of concurrent logins. With EvictOldest the next login evicts the extra sessions
+    pub async fn apply(
+        &self,
+        revocation: &SessionRevocation,
+        trader_id: &str,
+        new_session_user_agent: Option<&str>,
+    ) -> Result<usize, SessionLimitError> {
+        let now = DateTimeAsMicroseconds::now();
+        let sessions = revocation
+            .get_trader_sessions(trader_id)
+            .await
+            .map_err(SessionLimitError::StoreError)?;
+        let denylist = revocation.get_denylist();
+
+        // Revoked sessions can still be in the store until the delete reaches it
+        let active_sessions: Vec<_> = sessions
+            .iter()
+            .filter(|session| !session.is_expired(now))
+            .filter(|session| !denylist.is_revoked(session.get_session_token(), now))
+            .map(|session| ActiveSession {
+                session_token: session.get_session_token(),
+                created: session.get_created_or_expires(),
+                device_type: DeviceType::detect(session.user_agent.as_deref()),
+            })
+            .collect();
+
+        let to_evict = self
+            .select_sessions_to_evict(active_sessions, DeviceType::detect(new_session_user_agent));
+
+        if to_evict.is_empty() {
+            return Ok(0);
+        }
+
+        if self.on_limit_reached == SessionLimitAction::Refuse {
+            return Err(SessionLimitError::LimitReached);
+        }
+
+        for session_token in to_evict.iter() {
+            revocation
+                .revoke_session(session_token)
+                .await
+                .map_err(SessionLimitError::StoreError)?;
+        }
+
+        Ok(to_evict.len())
+    }
+
+    pub fn select_sessions_to_evict<'s>(
+        &self,
+        mut active_sessions: Vec<ActiveSession<'s>>,
+        new_device_type: DeviceType,
+    ) -> Vec<&'s str> {
+        active_sessions.sort_by_key(|session| session.created);
+
+        let mut result = Vec::new();
+
+        if let Some(max_per_device_type) = self.max_sessions_per_device_type {
+            let same_device_type = active_sessions
+                .iter()
+                .filter(|session| session.device_type == new_device_type)
+                .count();
+
+            if same_device_type >= max_per_device_type {
+                let evict_amount = same_device_type - max_per_device_type + 1;
+
+                let mut evicted = 0;
+                active_sessions.retain(|session| {
+                    if evicted < evict_amount && session.device_type == new_device_type {
+                        result.push(session.session_token);
+                        evicted += 1;
+                        return false;
+                    }
+
+                    true
+                });
+            }
+        }
+
+        if let Some(max_per_trader) = self.max_sessions_per_trader {
+            if active_sessions.len() >= max_per_trader {
+                let evict_amount = active_sessions.len() - max_per_trader + 1;
+
+                for session in active_sessions.iter().take(evict_amount) {
+                    result.push(session.session_token);
+                }
+            }
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{sync::Arc, time::Duration};
+
+    use super::*;
+    use crate::{
+        middlewares::SessionEntity,
+        session_store::{InMemorySessionStore, SessionDenylist},
+    };
+
+    fn session(session_token: &str, created: i64, device_type: DeviceType) -> ActiveSession<'_> {
+        ActiveSession {
+            session_token,
+            created,
+            device_type,
+        }
+    }
+
+    #[test]
+    fn test_evicts_oldest_per_trader() {
+        let policy = SessionLimitPolicy {
+            max_sessions_per_trader: Some(2),
+            max_sessions_per_device_type: None,
+            on_limit_reached: SessionLimitAction::EvictOldest,
+        };
+
+        let sessions = vec![
+            session("b", 2, DeviceType::Mobile),
+            session("a", 1, DeviceType::Desktop),
+            session("c", 3, DeviceType::Mobile),
+        ];
+
+        let result = policy.select_sessions_to_evict(sessions, DeviceType::Mobile);
+        assert_eq!(vec!["a", "b"], result);
+    }
+
+    #[test]
+    fn test_evicts_oldest_of_same_device_type() {
+        let policy = SessionLimitPolicy {
+            max_sessions_per_trader: Some(5),
+            max_sessions_per_device_type: Some(1),
+            on_limit_reached: SessionLimitAction::EvictOldest,
+        };
+
+        let sessions = vec![
+            session("a", 1, DeviceType::Desktop),
+            session("b", 2, DeviceType::Mobile),
+        ];
+
+        let result = policy.select_sessions_to_evict(sessions, DeviceType::Mobile);
+        assert_eq!(vec!["b"], result);
+    }
+
+    #[test]
+    fn test_nothing_to_evict_under_limits() {
+        let policy = SessionLimitPolicy {
+            max_sessions_per_trader: Some(5),
+            max_sessions_per_device_type: Some(2),
+            on_limit_reached: SessionLimitAction::Refuse,
+        };
+
+        let sessions = vec![session("a", 1, DeviceType::Mobile)];
+
+        let result = policy.select_sessions_to_evict(sessions, DeviceType::Mobile);
+        assert!(result.is_empty());
+    }
+
+    #[test]
+    fn test_device_type_detection() {
+        assert_eq!(
+            DeviceType::Mobile,
+            DeviceType::detect(Some(
+                "Mozilla/5.0 (iPhone; CPU iPhone OS 17_0 like Mac OS X)"
+            ))
+        );
+        assert_eq!(
+            DeviceType::Desktop,
+            DeviceType::detect(Some("Mozilla/5.0 (Windows NT 10.0; Win64; x64)"))
+        );
+        assert_eq!(DeviceType::Unknown, DeviceType::detect(None));
+    }
+
+    #[tokio::test]
+    async fn test_revoked_sessions_are_not_counted() {
+        let sessions = Arc::new(InMemorySessionStore::new());
+        let now = DateTimeAsMicroseconds::now();
+        let expires = DateTimeAsMicroseconds::new(now.unix_microseconds + 60_000_000);
+
+        sessions.insert(SessionEntity::new(
+            "token-1".to_string(),
+            "trader".to_string(),
+            expires,
+        ));
+        sessions.insert(SessionEntity::new(
+            "token-2".to_string(),
+            "trader".to_string(),
+            expires,
+        ));
+
+        let revocation = SessionRevocation::new(
+            sessions.clone(),
+            sessions,
+            Arc::new(SessionDenylist::new(Duration::from_secs(60))),
+        );
+
+        revocation.get_denylist().add("token-1", now);
+
+        let policy = SessionLimitPolicy {
+            max_sessions_per_trader: Some(2),
+            max_sessions_per_device_type: None,
+            on_limit_reached: SessionLimitAction::Refuse,
+        };
+
+        let evicted = policy.apply(&revocation, "trader", None).await.unwrap();
+
+        assert_eq!(evicted, 0);
+    }
+}
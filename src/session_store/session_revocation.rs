@@ -0,0 +1,156 @@
+use std::sync::Arc;
+
+use service_sdk::my_http_server::{HttpContext, HttpFailResult};
+use service_sdk::rust_extensions::date_time::DateTimeAsMicroseconds;
+
+use crate::{
+    middlewares::{GetSessionToken, SessionEntity, SessionTokenSources},
+    ApiResultStatus, GetClientId,
+};
+
+use super::{SessionDenylist, SessionStore, SessionWriter};
+
+pub struct SessionRevocation {
+    sessions_store: Arc<dyn SessionStore + Send + Sync + 'static>,
+    session_writer: Arc<dyn SessionWriter + Send + Sync + 'static>,
+    denylist: Arc<SessionDenylist>,
+    token_sources: SessionTokenSources,
+}
+
+impl SessionRevocation {
+    pub fn new(
+        sessions_store: Arc<dyn SessionStore + Send + Sync + 'static>,
+        session_writer: Arc<dyn SessionWriter + Send + Sync + 'static>,
+        denylist: Arc<SessionDenylist>,
+    ) -> Self {
+        Self {
+            sessions_store,
+            session_writer,
+            denylist,
+            token_sources: SessionTokenSources::default(),
+        }
+    }
+
+    // Has to match the sources AuthMiddleware is configured with
+    pub fn with_token_sources(mut self, token_sources: SessionTokenSources) -> Self {
+        self.token_sources = token_sources;
+        self
+    }
+
+    pub fn get_denylist(&self) -> Arc<SessionDenylist> {
+        self.denylist.clone()
+    }
+
+    // Fails if the store can not look up sessions of a trader (e.g. MyNoSqlSessionStore),
+    // so revoking them does not silently do nothing
+    pub async fn get_trader_sessions(
+        &self,
+        trader_id: &str,
+    ) -> Result<Vec<Arc<SessionEntity>>, String> {
+        self.sessions_store
+            .get_trader_sessions(trader_id)
+            .await
+            .ok_or_else(|| {
+                "Session store can not look up sessions of a trader. Use TraderIndexedSessionStore"
+                    .to_string()
+            })
+    }
+
+    pub async fn revoke_session(&self, session_token: &str) -> Result<(), String> {
+        self.denylist
+            .add(session_token, DateTimeAsMicroseconds::now());
+        self.session_writer.delete_session(session_token).await
+    }
+
+    // Returns amount of revoked sessions
+    pub async fn revoke_trader_sessions(&self, trader_id: &str) -> Result<usize, String> {
+        self.revoke_trader_sessions_except(trader_id, None).await
+    }
+
+    // Returns amount of revoked sessions
+    pub async fn revoke_trader_sessions_except(
+        &self,
+        trader_id: &str,
+        keep_session_token: Option<&str>,
+    ) -> Result<usize, String> {
+        let sessions = self.get_trader_sessions(trader_id).await?;
+
+        let mut revoked = 0;
+
+        for session in sessions {
+            let session_token = session.get_session_token();
+
+            if Some(session_token) == keep_session_token {
+                continue;
+            }
+
+            self.revoke_session(session_token).await?;
+            revoked += 1;
+        }
+
+        Ok(revoked)
+    }
+
+    // Handles "log out other devices" endpoint. Returns amount of revoked sessions
+    pub async fn log_out_other_devices(&self, ctx: &HttpContext) -> Result<usize, HttpFailResult> {
+        let trader_id = ctx.get_client_id()?;
+        let current_session = self.get_current_session(ctx, trader_id).await;
+
+        let current_session_token = current_session
+            .as_ref()
+            .map(|session| session.get_session_token());
+
+        self.revoke_trader_sessions_except(trader_id, current_session_token)
+            .await
+            .map_err(|_| ApiResultStatus::InternalError.into())
+    }
+
+    // Session the request was authenticated with
+    async fn get_current_session(
+        &self,
+        ctx: &HttpContext,
+        trader_id: &str,
+    ) -> Option<Arc<SessionEntity>> {
+        let session_token = ctx.get_session_token_from_sources(&self.token_sources)?;
+
+        let session = self
+            .sessions_store
+            .get_session(session_token.as_str())
+            .await?;
+
+        if session.trader_id != trader_id {
+            return None;
+        }
+
+        Some(session)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::session_store::InMemorySessionStore;
+
+    // Can not look up sessions of a trader, like MyNoSqlSessionStore
+    struct NotIndexedSessionStore;
+
+    #[async_trait::async_trait]
+    impl SessionStore for NotIndexedSessionStore {
+        async fn get_session(&self, _session_token: &str) -> Option<Arc<SessionEntity>> {
+            None
+        }
+    }
+
+    #[tokio::test]
+    async fn test_not_indexed_store_fails_to_revoke() {
+        let revocation = SessionRevocation::new(
+            Arc::new(NotIndexedSessionStore),
+            Arc::new(InMemorySessionStore::new()),
+            Arc::new(SessionDenylist::new(Duration::from_secs(10))),
+        );
+
+        assert!(revocation.revoke_trader_sessions("trader").await.is_err());
+    }
+}
@@ -0,0 +1,12 @@
+use std::sync::Arc;
+
+use crate::middlewares::SessionEntity;
+
+#[async_trait::async_trait]
+pub trait SessionStore {
+    async fn get_session(&self, session_token: &str) -> Option<Arc<SessionEntity>>;
+
+    async fn get_trader_sessions(&self, _trader_id: &str) -> Option<Vec<Arc<SessionEntity>>> {
+        None
+    }
+}
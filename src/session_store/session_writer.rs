@@ -0,0 +1,16 @@
+use crate::middlewares::SessionEntity;
+
+#[async_trait::async_trait]
+pub trait SessionWriter {
+    async fn save_session(&self, session_entity: SessionEntity) -> Result<(), String>;
+
+    // Applies the update to the stored session instead of a copy read earlier, so updates made
+    // in between (expiration, claims) are kept. A deleted session is not brought back: Ok(None)
+    async fn update_session(
+        &self,
+        session_token: &str,
+        update: &(dyn for<'s> Fn(&'s mut SessionEntity) + Send + Sync),
+    ) -> Result<Option<SessionEntity>, String>;
+
+    async fn delete_session(&self, session_token: &str) -> Result<(), String>;
+}
@@ -0,0 +1,67 @@
+use std::sync::{Arc, Mutex};
+
+use service_sdk::my_no_sql_sdk::reader::{MyNoSqlDataReaderCallBacks, MyNoSqlDataReaderTcp};
+
+use crate::middlewares::SessionEntity;
+
+use super::{SessionStore, TraderSessionsIndex};
+
+// Sessions reader which also finds sessions of a trader by index instead of scanning the table.
+// Subscribe with sessions_reader.assign_callback(store.clone()) so the index follows table updates
+pub struct TraderIndexedSessionStore {
+    sessions_reader: Arc<MyNoSqlDataReaderTcp<SessionEntity>>,
+    index: Mutex<TraderSessionsIndex>,
+}
+
+impl TraderIndexedSessionStore {
+    pub fn new(sessions_reader: Arc<MyNoSqlDataReaderTcp<SessionEntity>>) -> Self {
+        Self {
+            sessions_reader,
+            index: Mutex::new(TraderSessionsIndex::new()),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl SessionStore for TraderIndexedSessionStore {
+    async fn get_session(&self, session_token: &str) -> Option<Arc<SessionEntity>> {
+        self.sessions_reader.get_session(session_token).await
+    }
+
+    async fn get_trader_sessions(&self, trader_id: &str) -> Option<Vec<Arc<SessionEntity>>> {
+        let session_tokens = self.index.lock().unwrap().get_session_tokens(trader_id);
+
+        let mut result = Vec::with_capacity(session_tokens.len());
+
+        for session_token in session_tokens {
+            if let Some(session) = self.sessions_reader.get_session(&session_token).await {
+                result.push(session);
+            }
+        }
+
+        if result.is_empty() {
+            return None;
+        }
+
+        Some(result)
+    }
+}
+
+#[async_trait::async_trait]
+impl MyNoSqlDataReaderCallBacks<SessionEntity> for TraderIndexedSessionStore {
+    async fn inserted_or_replaced(&self, _partition_key: &str, entities: Vec<Arc<SessionEntity>>) {
+        let mut index = self.index.lock().unwrap();
+
+        for entity in entities {
+            index.insert(&entity);
+        }
+    }
+
+    async fn deleted(&self, _partition_key: &str, entities: Vec<Arc<SessionEntity>>) {
+        let mut index = self.index.lock().unwrap();
+
+        for entity in entities {
+            index.remove(&entity);
+        }
+    }
+}
@@ -0,0 +1,75 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::middlewares::SessionEntity;
+
+// trader id -> session tokens. Lets stores find sessions of a trader without scanning all of them
+#[derive(Debug, Default)]
+pub struct TraderSessionsIndex {
+    trader_sessions: HashMap<String, HashSet<String>>,
+}
+
+impl TraderSessionsIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, session_entity: &SessionEntity) {
+        self.trader_sessions
+            .entry(session_entity.trader_id.clone())
+            .or_default()
+            .insert(session_entity.get_session_token().to_string());
+    }
+
+    pub fn remove(&mut self, session_entity: &SessionEntity) {
+        let Some(sessions) = self.trader_sessions.get_mut(&session_entity.trader_id) else {
+            return;
+        };
+
+        sessions.remove(session_entity.get_session_token());
+
+        if sessions.is_empty() {
+            self.trader_sessions.remove(&session_entity.trader_id);
+        }
+    }
+
+    pub fn get_session_tokens(&self, trader_id: &str) -> Vec<String> {
+        match self.trader_sessions.get(trader_id) {
+            Some(sessions) => sessions.iter().cloned().collect(),
+            None => Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use service_sdk::rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use super::*;
+
+    fn create_session(session_token: &str, trader_id: &str) -> SessionEntity {
+        SessionEntity::new(
+            session_token.to_string(),
+            trader_id.to_string(),
+            DateTimeAsMicroseconds::new(0),
+        )
+    }
+
+    #[test]
+    fn test_index() {
+        let mut index = TraderSessionsIndex::new();
+
+        index.insert(&create_session("token-1", "trader-1"));
+        index.insert(&create_session("token-2", "trader-1"));
+        index.insert(&create_session("token-3", "trader-2"));
+
+        let mut tokens = index.get_session_tokens("trader-1");
+        tokens.sort();
+        assert_eq!(tokens, vec!["token-1", "token-2"]);
+
+        index.remove(&create_session("token-1", "trader-1"));
+        index.remove(&create_session("token-2", "trader-1"));
+
+        assert!(index.get_session_tokens("trader-1").is_empty());
+        assert_eq!(index.get_session_tokens("trader-2"), vec!["token-3"]);
+    }
+}
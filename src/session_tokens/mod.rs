@@ -0,0 +1,4 @@
+mod session_token_format;
+pub use session_token_format::*;
+mod session_token_generator;
+pub use session_token_generator::*;
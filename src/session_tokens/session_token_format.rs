@@ -0,0 +1,93 @@
+pub const TOKEN_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+pub const PREFIX_SEPARATOR: char = '.';
+
+const CHECKSUM_LENGTH: usize = 4;
+
+// Syntax of tokens minted by SessionTokenGenerator: [{prefix}.]{body}[{checksum}]
+#[derive(Debug, Clone)]
+pub struct SessionTokenFormat {
+    pub body_length: usize,
+    pub prefix: Option<String>,
+    pub with_checksum: bool,
+}
+
+impl SessionTokenFormat {
+    pub fn get_token_length(&self) -> usize {
+        let mut result = self.body_length;
+
+        if let Some(prefix) = self.prefix.as_ref() {
+            result += prefix.len() + 1;
+        }
+
+        if self.with_checksum {
+            result += CHECKSUM_LENGTH;
+        }
+
+        result
+    }
+
+    pub fn is_valid(&self, token: &str) -> bool {
+        if token.len() != self.get_token_length() {
+            return false;
+        }
+
+        let token_body = match self.prefix.as_ref() {
+            Some(prefix) => {
+                let without_prefix = match token.strip_prefix(prefix.as_str()) {
+                    Some(value) => value,
+                    None => return false,
+                };
+
+                match without_prefix.strip_prefix(PREFIX_SEPARATOR) {
+                    Some(value) => value,
+                    None => return false,
+                }
+            }
+            None => token,
+        };
+
+        if !token_body.bytes().all(|b| TOKEN_ALPHABET.contains(&b)) {
+            return false;
+        }
+
+        if !self.with_checksum {
+            return true;
+        }
+
+        let (signed, checksum) = token.split_at(token.len() - CHECKSUM_LENGTH);
+
+        calc_checksum(signed).as_bytes() == checksum.as_bytes()
+    }
+}
+
+impl Default for SessionTokenFormat {
+    fn default() -> Self {
+        Self {
+            body_length: 43,
+            prefix: None,
+            with_checksum: false,
+        }
+    }
+}
+
+// Not a security feature: lets us drop mistyped or garbage tokens without a store lookup
+pub(crate) fn calc_checksum(src: &str) -> String {
+    // FNV-1a 32 bit
+    let mut hash: u32 = 0x811c9dc5;
+
+    for b in src.bytes() {
+        hash ^= b as u32;
+        hash = hash.wrapping_mul(0x01000193);
+    }
+
+    let mut result = String::with_capacity(CHECKSUM_LENGTH);
+
+    for i in 0..CHECKSUM_LENGTH {
+        let index = (hash >> (i * 6)) & 0x3f;
+        result.push(TOKEN_ALPHABET[index as usize] as char);
+    }
+
+    result
+}
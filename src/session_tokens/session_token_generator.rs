@@ -0,0 +1,96 @@
+use rand::{rngs::OsRng, Rng};
+
+use super::{calc_checksum, SessionTokenFormat, PREFIX_SEPARATOR, TOKEN_ALPHABET};
+
+pub struct SessionTokenGenerator {
+    format: SessionTokenFormat,
+}
+
+impl SessionTokenGenerator {
+    pub fn new(format: SessionTokenFormat) -> Self {
+        Self { format }
+    }
+
+    pub fn get_format(&self) -> &SessionTokenFormat {
+        &self.format
+    }
+
+    pub fn generate(&self) -> String {
+        let mut result = String::with_capacity(self.format.get_token_length());
+
+        if let Some(prefix) = self.format.prefix.as_ref() {
+            result.push_str(prefix);
+            result.push(PREFIX_SEPARATOR);
+        }
+
+        let mut rng = OsRng;
+
+        for _ in 0..self.format.body_length {
+            let index = rng.gen_range(0..TOKEN_ALPHABET.len());
+            result.push(TOKEN_ALPHABET[index] as char);
+        }
+
+        if self.format.with_checksum {
+            let checksum = calc_checksum(&result);
+            result.push_str(&checksum);
+        }
+
+        result
+    }
+}
+
+impl Default for SessionTokenGenerator {
+    fn default() -> Self {
+        Self::new(SessionTokenFormat::default())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_generated_tokens_are_valid() {
+        let format = SessionTokenFormat {
+            body_length: 32,
+            prefix: Some("v1".to_string()),
+            with_checksum: true,
+        };
+
+        let generator = SessionTokenGenerator::new(format.clone());
+
+        for _ in 0..100 {
+            let token = generator.generate();
+            assert_eq!(format.get_token_length(), token.len());
+            assert!(token.starts_with("v1."));
+            assert!(format.is_valid(&token));
+        }
+    }
+
+    #[test]
+    fn test_tokens_are_unique() {
+        let generator = SessionTokenGenerator::default();
+        assert_ne!(generator.generate(), generator.generate());
+    }
+
+    #[test]
+    fn test_invalid_tokens() {
+        let format = SessionTokenFormat {
+            body_length: 8,
+            prefix: Some("v1".to_string()),
+            with_checksum: true,
+        };
+
+        let token = SessionTokenGenerator::new(format.clone()).generate();
+
+        let mut broken_checksum = token.clone();
+        let last = broken_checksum.pop().unwrap();
+        broken_checksum.push(if last == 'A' { 'B' } else { 'A' });
+
+        assert!(!format.is_valid(&broken_checksum));
+        assert!(!format.is_valid(&token.replacen("v1", "v2", 1)));
+        assert!(!format.is_valid(&token[1..]));
+        assert!(!format.is_valid("v1.abc+/===xxxx"));
+        assert!(!format.is_valid(""));
+    }
+}
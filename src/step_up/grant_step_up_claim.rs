@@ -0,0 +1,252 @@
+use std::time::Duration;
+
+use service_sdk::rust_extensions::date_time::DateTimeAsMicroseconds;
+
+use crate::{
+    middlewares::{duration_as_micros, SessionEntity},
+    session_store::SessionWriter,
+    ApiResultStatus,
+};
+
+use super::{SecondFactorVerifier, StepUpAttemptLimiter};
+
+pub struct StepUpClaimRequest<'s> {
+    pub claim_name: &'s str,
+    pub code: &'s str,
+    pub claim_lifetime: Duration,
+    pub request_ip: &'s str,
+}
+
+// Verifies the second factor and grants a short-lived claim bound to the ip the code came from.
+// Err is a store failure, Ok(TwoFaCodeIsInvalid) is a wrong code.
+// Once the limiter blocks the trader the code is not checked at all.
+// The claim is added to the stored session, so updates made since `session_entity` was read are kept
+pub async fn grant_step_up_claim(
+    verifier: &(dyn SecondFactorVerifier + Send + Sync),
+    attempt_limiter: &StepUpAttemptLimiter,
+    session_writer: &(dyn SessionWriter + Send + Sync),
+    session_entity: &SessionEntity,
+    request: StepUpClaimRequest<'_>,
+) -> Result<ApiResultStatus, String> {
+    let now = DateTimeAsMicroseconds::now();
+    let trader_id = session_entity.trader_id.as_str();
+
+    if !attempt_limiter.try_begin_attempt(trader_id, now) {
+        return Ok(ApiResultStatus::TwoFaAttemptsLimitReached);
+    }
+
+    if !verifier.verify(trader_id, request.code).await {
+        return Ok(ApiResultStatus::TwoFaCodeIsInvalid);
+    }
+
+    attempt_limiter.reset(trader_id);
+
+    let expires = DateTimeAsMicroseconds::new(
+        now.unix_microseconds
+            .saturating_add(duration_as_micros(request.claim_lifetime)),
+    );
+
+    let updated = session_writer
+        .update_session(session_entity.get_session_token(), &|session_entity| {
+            session_entity.set_claim(
+                request.claim_name.to_string(),
+                expires,
+                Some(request.request_ip.to_string()),
+            )
+        })
+        .await?;
+
+    // Session was revoked while the code was checked
+    if updated.is_none() {
+        return Ok(ApiResultStatus::TokenIsInvalid);
+    }
+
+    Ok(ApiResultStatus::Ok)
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use service_sdk::rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use super::{grant_step_up_claim, StepUpClaimRequest};
+    use crate::middlewares::SessionEntity;
+    use crate::session_store::{InMemorySessionStore, SessionStore};
+    use crate::step_up::{SecondFactorVerifier, StepUpAttemptLimiter};
+    use crate::ApiResultStatus;
+
+    struct FixedCodeVerifier;
+
+    #[async_trait::async_trait]
+    impl SecondFactorVerifier for FixedCodeVerifier {
+        fn get_factor_name(&self) -> &str {
+            "test"
+        }
+
+        async fn verify(&self, _trader_id: &str, code: &str) -> bool {
+            code == "123456"
+        }
+    }
+
+    // Answers after other requests got a chance to run
+    struct SlowVerifier;
+
+    #[async_trait::async_trait]
+    impl SecondFactorVerifier for SlowVerifier {
+        fn get_factor_name(&self) -> &str {
+            "test"
+        }
+
+        async fn verify(&self, _trader_id: &str, _code: &str) -> bool {
+            tokio::task::yield_now().await;
+            false
+        }
+    }
+
+    fn request(code: &str) -> StepUpClaimRequest<'_> {
+        StepUpClaimRequest {
+            claim_name: "withdraw",
+            code,
+            claim_lifetime: Duration::from_secs(300),
+            request_ip: "10.0.0.1",
+        }
+    }
+
+    fn create_session() -> SessionEntity {
+        SessionEntity::new(
+            "token".to_string(),
+            "trader".to_string(),
+            DateTimeAsMicroseconds::now().add(Duration::from_secs(3600)),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_claim_is_granted_for_valid_code() {
+        let store = InMemorySessionStore::new();
+        let limiter = StepUpAttemptLimiter::default();
+        let session_entity = create_session();
+        store.insert(session_entity.clone());
+
+        let result = grant_step_up_claim(
+            &FixedCodeVerifier,
+            &limiter,
+            &store,
+            &session_entity,
+            request("000000"),
+        )
+        .await
+        .unwrap();
+        assert!(matches!(result, ApiResultStatus::TwoFaCodeIsInvalid));
+        assert!(store.get_session("token").await.unwrap().claims.is_none());
+
+        let result = grant_step_up_claim(
+            &FixedCodeVerifier,
+            &limiter,
+            &store,
+            &session_entity,
+            request("123456"),
+        )
+        .await
+        .unwrap();
+        assert!(matches!(result, ApiResultStatus::Ok));
+
+        let saved = store.get_session("token").await.unwrap();
+        let claim = &saved.claims.as_ref().unwrap()[0];
+        assert_eq!(claim.name, "withdraw");
+        assert_eq!(claim.ip.as_deref(), Some("10.0.0.1"));
+    }
+
+    #[tokio::test]
+    async fn test_valid_code_is_rejected_after_too_many_attempts() {
+        let store = InMemorySessionStore::new();
+        let limiter = StepUpAttemptLimiter::new(2, Duration::from_secs(60));
+        let session_entity = create_session();
+        store.insert(session_entity.clone());
+
+        for _ in 0..2 {
+            grant_step_up_claim(
+                &FixedCodeVerifier,
+                &limiter,
+                &store,
+                &session_entity,
+                request("000000"),
+            )
+            .await
+            .unwrap();
+        }
+
+        let result = grant_step_up_claim(
+            &FixedCodeVerifier,
+            &limiter,
+            &store,
+            &session_entity,
+            request("123456"),
+        )
+        .await
+        .unwrap();
+
+        assert!(matches!(result, ApiResultStatus::TwoFaAttemptsLimitReached));
+        assert!(store.get_session("token").await.unwrap().claims.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_parallel_codes_do_not_pass_the_limit() {
+        let store = InMemorySessionStore::new();
+        let limiter = StepUpAttemptLimiter::new(2, Duration::from_secs(60));
+        let session_entity = create_session();
+        store.insert(session_entity.clone());
+
+        let results = tokio::join!(
+            grant_step_up_claim(
+                &SlowVerifier,
+                &limiter,
+                &store,
+                &session_entity,
+                request("1")
+            ),
+            grant_step_up_claim(
+                &SlowVerifier,
+                &limiter,
+                &store,
+                &session_entity,
+                request("2")
+            ),
+            grant_step_up_claim(
+                &SlowVerifier,
+                &limiter,
+                &store,
+                &session_entity,
+                request("3")
+            ),
+        );
+
+        let results = [results.0.unwrap(), results.1.unwrap(), results.2.unwrap()];
+
+        let limited = results
+            .iter()
+            .filter(|result| matches!(result, ApiResultStatus::TwoFaAttemptsLimitReached))
+            .count();
+
+        assert_eq!(limited, 1);
+    }
+
+    #[tokio::test]
+    async fn test_claim_is_not_granted_to_revoked_session() {
+        let store = InMemorySessionStore::new();
+        let limiter = StepUpAttemptLimiter::default();
+
+        let result = grant_step_up_claim(
+            &FixedCodeVerifier,
+            &limiter,
+            &store,
+            &create_session(),
+            request("123456"),
+        )
+        .await
+        .unwrap();
+
+        assert!(matches!(result, ApiResultStatus::TokenIsInvalid));
+        assert!(store.get_session("token").await.is_none());
+    }
+}
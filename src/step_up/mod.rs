@@ -0,0 +1,8 @@
+mod second_factor_verifier;
+pub use second_factor_verifier::*;
+mod step_up_attempt_limiter;
+pub use step_up_attempt_limiter::*;
+mod grant_step_up_claim;
+pub use grant_step_up_claim::*;
+mod step_up_auth_error_factory;
+pub use step_up_auth_error_factory::*;
@@ -0,0 +1,7 @@
+#[async_trait::async_trait]
+pub trait SecondFactorVerifier {
+    // Name the client sees in StepUpRequiredApiResponse.factor, e.g. "totp", "sms", "email"
+    fn get_factor_name(&self) -> &str;
+
+    async fn verify(&self, trader_id: &str, code: &str) -> bool;
+}
@@ -0,0 +1,132 @@
+use std::{collections::HashMap, sync::Mutex, time::Duration};
+
+use service_sdk::rust_extensions::date_time::DateTimeAsMicroseconds;
+
+use crate::middlewares::duration_as_micros;
+
+struct Attempts {
+    count: u32,
+    window_started: i64,
+}
+
+const PRUNE_THRESHOLD: usize = 1024;
+
+// Counts second factor attempts per trader. After `max_attempts` within `window` the trader is
+// blocked until the window ends, so a 6 digit code can not be brute forced.
+// An attempt is reserved before the code is checked and the counter is reset only on success,
+// so codes sent in parallel can not pass the limit before the first failure is counted
+pub struct StepUpAttemptLimiter {
+    max_attempts: u32,
+    window: Duration,
+    attempts: Mutex<StepUpAttempts>,
+}
+
+struct StepUpAttempts {
+    by_trader: HashMap<String, Attempts>,
+    prune_at: usize,
+}
+
+impl StepUpAttemptLimiter {
+    pub fn new(max_attempts: u32, window: Duration) -> Self {
+        Self {
+            max_attempts,
+            window,
+            attempts: Mutex::new(StepUpAttempts {
+                by_trader: HashMap::new(),
+                prune_at: PRUNE_THRESHOLD,
+            }),
+        }
+    }
+
+    pub fn is_blocked(&self, trader_id: &str, now: DateTimeAsMicroseconds) -> bool {
+        let window_started = self.get_window_started(now);
+        let attempts = self.attempts.lock().unwrap();
+
+        match attempts.by_trader.get(trader_id) {
+            Some(attempts) => {
+                attempts.window_started > window_started && attempts.count >= self.max_attempts
+            }
+            None => false,
+        }
+    }
+
+    // False once the limit is reached. The attempt is counted until `reset` is called
+    pub fn try_begin_attempt(&self, trader_id: &str, now: DateTimeAsMicroseconds) -> bool {
+        let window_started = self.get_window_started(now);
+        let mut attempts = self.attempts.lock().unwrap();
+
+        // Traders which never succeed are dropped once their window ends
+        if attempts.by_trader.len() >= attempts.prune_at {
+            attempts
+                .by_trader
+                .retain(|_, attempts| attempts.window_started > window_started);
+            attempts.prune_at = PRUNE_THRESHOLD.max(attempts.by_trader.len() * 2);
+        }
+
+        let trader_attempts = attempts
+            .by_trader
+            .entry(trader_id.to_string())
+            .or_insert(Attempts {
+                count: 0,
+                window_started: now.unix_microseconds,
+            });
+
+        if trader_attempts.window_started <= window_started {
+            trader_attempts.count = 0;
+            trader_attempts.window_started = now.unix_microseconds;
+        }
+
+        if trader_attempts.count >= self.max_attempts {
+            return false;
+        }
+
+        trader_attempts.count += 1;
+        true
+    }
+
+    pub fn reset(&self, trader_id: &str) {
+        self.attempts.lock().unwrap().by_trader.remove(trader_id);
+    }
+
+    // Attempts registered before this moment are outside of the window
+    fn get_window_started(&self, now: DateTimeAsMicroseconds) -> i64 {
+        now.unix_microseconds
+            .saturating_sub(duration_as_micros(self.window))
+    }
+}
+
+impl Default for StepUpAttemptLimiter {
+    fn default() -> Self {
+        Self::new(5, Duration::from_secs(60 * 15))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use service_sdk::rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use super::StepUpAttemptLimiter;
+
+    #[test]
+    fn test_blocked_until_window_ends() {
+        let limiter = StepUpAttemptLimiter::new(2, Duration::from_secs(60));
+        let now = DateTimeAsMicroseconds::new(1_000_000_000);
+
+        assert!(limiter.try_begin_attempt("trader", now));
+        assert!(!limiter.is_blocked("trader", now));
+
+        assert!(limiter.try_begin_attempt("trader", now));
+        assert!(limiter.is_blocked("trader", now));
+        assert!(!limiter.try_begin_attempt("trader", now));
+        assert!(!limiter.is_blocked("other", now));
+
+        let after_window = now.add(Duration::from_secs(60));
+        assert!(!limiter.is_blocked("trader", after_window));
+        assert!(limiter.try_begin_attempt("trader", after_window));
+
+        limiter.reset("trader");
+        assert!(!limiter.is_blocked("trader", now));
+    }
+}
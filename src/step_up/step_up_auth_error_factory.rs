@@ -0,0 +1,148 @@
+use std::collections::HashMap;
+
+use my_http_server::controllers::documentation::{
+    data_types::HttpDataType, out_results::HttpResult,
+};
+use my_http_server::controllers::AuthErrorFactory;
+use my_http_server::macros::MyHttpObjectStructure;
+use my_http_server::HttpFailResult;
+use serde::Serialize;
+use service_sdk::my_http_server;
+
+use crate::error_responses::{ErrorResponse, ProblemDetails};
+use crate::{into_http_fail_result, middlewares::AuthFailResponseFactory, ApiResultStatus};
+
+use super::SecondFactorVerifier;
+
+#[derive(Serialize, Debug, MyHttpObjectStructure)]
+pub struct StepUpRequiredApiResponse {
+    pub status: ApiResultStatus,
+    pub claim: String,
+    pub factor: String,
+}
+
+impl StepUpRequiredApiResponse {
+    pub fn new(claim: String, factor: String) -> HttpFailResult {
+        StepUpRequiredApiResponse {
+            status: ApiResultStatus::AccessClaimRequired,
+            claim,
+            factor,
+        }
+        .into_fail_result()
+    }
+}
+
+impl ErrorResponse for StepUpRequiredApiResponse {
+    fn get_result_status(&self) -> i16 {
+        self.status as i16
+    }
+
+    fn into_legacy_fail_result(self, message: Option<&str>) -> HttpFailResult {
+        into_http_fail_result(&self, &self.status, message)
+    }
+
+    fn into_problem_details(self) -> ProblemDetails {
+        ProblemDetails::new(&self.status).with_data(&serde_json::json!({
+            "claim": self.claim,
+            "factor": self.factor,
+        }))
+    }
+}
+
+// Same as AuthFailResponseFactory, but for claims granted by step-up tells the client which factor to present.
+// Set it with http_server_builder.set_auth_error_factory after configure_rest_api_server
+pub struct StepUpAuthErrorFactory {
+    // claim name -> factor name
+    step_up_claims: HashMap<String, String>,
+}
+
+impl StepUpAuthErrorFactory {
+    pub fn new() -> Self {
+        Self {
+            step_up_claims: HashMap::new(),
+        }
+    }
+
+    // The client is told to present the factor of the verifier which grants the claim
+    pub fn add_step_up_claim(
+        mut self,
+        claim: impl Into<String>,
+        verifier: &(dyn SecondFactorVerifier + Send + Sync),
+    ) -> Self {
+        self.step_up_claims
+            .insert(claim.into(), verifier.get_factor_name().to_string());
+        self
+    }
+}
+
+impl Default for StepUpAuthErrorFactory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AuthErrorFactory for StepUpAuthErrorFactory {
+    fn get_not_authenticated(&self) -> HttpFailResult {
+        AuthFailResponseFactory.get_not_authenticated()
+    }
+
+    fn get_not_authorized(&self, claim_name: String) -> HttpFailResult {
+        match self.step_up_claims.get(&claim_name) {
+            Some(factor) => StepUpRequiredApiResponse::new(claim_name, factor.to_string()),
+            None => AuthFailResponseFactory.get_not_authorized(claim_name),
+        }
+    }
+
+    fn get_global_http_fail_result_types(&self) -> Option<Vec<HttpResult>> {
+        let mut result = AuthFailResponseFactory
+            .get_global_http_fail_result_types()
+            .unwrap_or_default();
+
+        result.push(HttpResult {
+            http_code: 403,
+            nullable: false,
+            description: "Step-up authentication required. Present the factor and retry"
+                .to_string(),
+            data_type: HttpDataType::Object(StepUpRequiredApiResponse::get_http_data_structure()),
+        });
+
+        Some(result)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use service_sdk::my_http_server::controllers::AuthErrorFactory;
+
+    use super::StepUpAuthErrorFactory;
+    use crate::step_up::SecondFactorVerifier;
+
+    struct SmsVerifier;
+
+    #[async_trait::async_trait]
+    impl SecondFactorVerifier for SmsVerifier {
+        fn get_factor_name(&self) -> &str {
+            "sms"
+        }
+
+        async fn verify(&self, _trader_id: &str, _code: &str) -> bool {
+            false
+        }
+    }
+
+    #[test]
+    fn test_step_up_claim_names_the_factor() {
+        let factory = StepUpAuthErrorFactory::new().add_step_up_claim("withdraw", &SmsVerifier);
+
+        let result = factory.get_not_authorized("withdraw".to_string());
+        assert_eq!(result.status_code, 403);
+
+        let body: serde_json::Value = serde_json::from_slice(&result.content).unwrap();
+        assert_eq!(body["factor"], "sms");
+        assert_eq!(body["claim"], "withdraw");
+
+        let result = factory.get_not_authorized("trading".to_string());
+        let body: serde_json::Value = serde_json::from_slice(&result.content).unwrap();
+        assert!(body.get("factor").is_none());
+    }
+}
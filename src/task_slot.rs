@@ -0,0 +1,151 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Mutex, OnceLock},
+    time::{Duration, Instant},
+};
+
+use tokio::task;
+
+// Values which are not taken (e.g. of requests to public routes) are pruned after this time
+const KEEP_TIME: Duration = Duration::from_secs(30);
+const MAX_VALUES: usize = 65536;
+
+// Keeps one value per tokio task, so a value set by a middleware is visible to AuthErrorFactory
+// and Into<HttpFailResult>, which are called without HttpContext. Outside of a task nothing is kept.
+// HTTP/2 streams are served on tasks of their own, while a keep-alive connection serves its
+// requests one after another on one task. So the value has to be replaced at the start of
+// each request, otherwise a value of the previous request is seen
+pub(crate) struct TaskSlot<T: Clone> {
+    values: OnceLock<Mutex<TaskSlotValues<T>>>,
+}
+
+struct TaskSlotValues<T> {
+    by_task: HashMap<task::Id, (T, Instant)>,
+    // In order of setting, so expired values are pruned from the front without scanning all of them
+    set_order: VecDeque<(task::Id, Instant)>,
+}
+
+impl<T> TaskSlotValues<T> {
+    fn prune(&mut self, now: Instant) {
+        while let Some((task_id, set_at)) = self.set_order.front().copied() {
+            let is_expired = now.duration_since(set_at) >= KEEP_TIME;
+
+            if !is_expired && self.set_order.len() < MAX_VALUES {
+                break;
+            }
+
+            self.set_order.pop_front();
+
+            // The task could set a newer value since then
+            if let Some((_, value_set_at)) = self.by_task.get(&task_id) {
+                if *value_set_at == set_at {
+                    self.by_task.remove(&task_id);
+                }
+            }
+        }
+    }
+}
+
+impl<T: Clone> TaskSlot<T> {
+    pub const fn new() -> Self {
+        Self {
+            values: OnceLock::new(),
+        }
+    }
+
+    fn get_values(&self) -> &Mutex<TaskSlotValues<T>> {
+        self.values.get_or_init(|| {
+            Mutex::new(TaskSlotValues {
+                by_task: HashMap::new(),
+                set_order: VecDeque::new(),
+            })
+        })
+    }
+
+    pub fn set(&self, value: T) {
+        let Some(task_id) = task::try_id() else {
+            return;
+        };
+
+        let now = Instant::now();
+        let mut values = self.get_values().lock().unwrap();
+
+        values.prune(now);
+        values.by_task.insert(task_id, (value, now));
+        values.set_order.push_back((task_id, now));
+    }
+
+    pub fn get(&self) -> Option<T> {
+        let task_id = task::try_id()?;
+        let values = self.get_values().lock().unwrap();
+        values.by_task.get(&task_id).map(|(value, _)| value.clone())
+    }
+
+    // Starts from the default value if nothing was set by the task
+    pub fn update<TResult>(&self, update: impl FnOnce(&mut T) -> TResult) -> Option<TResult>
+    where
+        T: Default,
+    {
+        let task_id = task::try_id()?;
+        let mut values = self.get_values().lock().unwrap();
+
+        if let Some((value, _)) = values.by_task.get_mut(&task_id) {
+            return Some(update(value));
+        }
+
+        let now = Instant::now();
+        let mut value = T::default();
+        let result = update(&mut value);
+
+        values.prune(now);
+        values.by_task.insert(task_id, (value, now));
+        values.set_order.push_back((task_id, now));
+
+        Some(result)
+    }
+
+    #[cfg(test)]
+    fn len(&self) -> usize {
+        self.get_values().lock().unwrap().by_task.len()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_value_is_replaced_per_task() {
+        static SLOT: TaskSlot<u32> = TaskSlot::new();
+
+        tokio::spawn(async {
+            SLOT.set(1);
+            SLOT.set(2);
+
+            assert_eq!(SLOT.get(), Some(2));
+            assert_eq!(SLOT.len(), 1);
+
+            assert_eq!(SLOT.update(std::mem::take), Some(2));
+            assert_eq!(SLOT.get(), Some(0));
+        })
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_expired_values_are_pruned() {
+        let slot = std::sync::Arc::new(TaskSlot::new());
+
+        for value in 0..3 {
+            let slot = slot.clone();
+            tokio::spawn(async move { slot.set(value) }).await.unwrap();
+        }
+
+        assert_eq!(slot.len(), 3);
+
+        let later = Instant::now() + KEEP_TIME;
+        slot.get_values().lock().unwrap().prune(later);
+
+        assert_eq!(slot.len(), 0);
+    }
+}
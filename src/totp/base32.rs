@@ -0,0 +1,79 @@
+// RFC 4648 base32 without padding, the form authenticator apps expect in otpauth:// URIs
+const ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+pub fn base32_encode(src: &[u8]) -> String {
+    let mut result = String::with_capacity((src.len() * 8).div_ceil(5));
+
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+
+    for b in src {
+        buffer = (buffer << 8) | *b as u32;
+        bits += 8;
+
+        while bits >= 5 {
+            bits -= 5;
+            result.push(ALPHABET[((buffer >> bits) & 0x1f) as usize] as char);
+        }
+    }
+
+    if bits > 0 {
+        result.push(ALPHABET[((buffer << (5 - bits)) & 0x1f) as usize] as char);
+    }
+
+    result
+}
+
+// Case-insensitive; spaces, dashes and padding are ignored
+pub fn base32_decode(src: &str) -> Option<Vec<u8>> {
+    let mut result = Vec::with_capacity(src.len() * 5 / 8);
+
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+
+    for c in src.bytes() {
+        if matches!(c, b' ' | b'-' | b'=') {
+            continue;
+        }
+
+        let value = ALPHABET
+            .iter()
+            .position(|itm| *itm == c.to_ascii_uppercase())?;
+
+        buffer = (buffer << 5) | value as u32;
+        bits += 5;
+
+        if bits >= 8 {
+            bits -= 8;
+            result.push((buffer >> bits) as u8);
+        }
+    }
+
+    Some(result)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_rfc4648_vectors() {
+        assert_eq!("", base32_encode(b""));
+        assert_eq!("MY", base32_encode(b"f"));
+        assert_eq!("MZXQ", base32_encode(b"fo"));
+        assert_eq!("MZXW6", base32_encode(b"foo"));
+        assert_eq!("MZXW6YQ", base32_encode(b"foob"));
+        assert_eq!("MZXW6YTB", base32_encode(b"fooba"));
+        assert_eq!("MZXW6YTBOI", base32_encode(b"foobar"));
+    }
+
+    #[test]
+    fn test_decode() {
+        assert_eq!(Some(b"foobar".to_vec()), base32_decode("MZXW6YTBOI"));
+        assert_eq!(
+            Some(b"foobar".to_vec()),
+            base32_decode("mzxw 6ytb oi======")
+        );
+        assert_eq!(None, base32_decode("MZXW1"));
+    }
+}
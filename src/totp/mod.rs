@@ -0,0 +1,8 @@
+mod base32;
+pub use base32::*;
+mod totp_settings;
+pub use totp_settings::*;
+mod totp_replay_guard;
+pub use totp_replay_guard::*;
+mod totp_verifier;
+pub use totp_verifier::*;
@@ -0,0 +1,57 @@
+use std::{collections::HashMap, sync::Mutex};
+
+// Remembers the last step each trader used, so a code can not be used twice inside its step.
+// Kept in memory of this instance only: replicas do not see steps used on each other
+pub struct TotpReplayGuard {
+    last_used_counters: Mutex<HashMap<String, u64>>,
+}
+
+impl TotpReplayGuard {
+    pub fn new() -> Self {
+        Self {
+            last_used_counters: Mutex::new(HashMap::new()),
+        }
+    }
+
+    // Returns false if the step (or a later one) was already used by the trader
+    pub fn try_use(&self, trader_id: &str, counter: u64) -> bool {
+        let mut last_used_counters = self.last_used_counters.lock().unwrap();
+
+        if let Some(last_used) = last_used_counters.get(trader_id) {
+            if *last_used >= counter {
+                return false;
+            }
+        }
+
+        last_used_counters.insert(trader_id.to_string(), counter);
+        true
+    }
+
+    // Drops traders whose last step is too old to be replayed
+    pub fn gc(&self, min_counter: u64) {
+        let mut last_used_counters = self.last_used_counters.lock().unwrap();
+        last_used_counters.retain(|_, counter| *counter >= min_counter);
+    }
+}
+
+impl Default for TotpReplayGuard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::TotpReplayGuard;
+
+    #[test]
+    fn test_code_can_not_be_reused() {
+        let guard = TotpReplayGuard::new();
+
+        assert!(guard.try_use("trader", 10));
+        assert!(!guard.try_use("trader", 10));
+        assert!(!guard.try_use("trader", 9));
+        assert!(guard.try_use("other-trader", 10));
+        assert!(guard.try_use("trader", 11));
+    }
+}
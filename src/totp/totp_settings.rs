@@ -0,0 +1,296 @@
+use hmac::{Hmac, Mac};
+use rand::{rngs::OsRng, RngCore};
+use sha1::Sha1;
+use sha2::{Sha256, Sha512};
+
+use crate::middlewares::constant_time_eq;
+
+use super::{base32_decode, base32_encode};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TotpAlgorithm {
+    Sha1,
+    Sha256,
+    Sha512,
+}
+
+impl TotpAlgorithm {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TotpAlgorithm::Sha1 => "SHA1",
+            TotpAlgorithm::Sha256 => "SHA256",
+            TotpAlgorithm::Sha512 => "SHA512",
+        }
+    }
+}
+
+// RFC 6238. Defaults are the ones every authenticator app supports: SHA1, 6 digits, 30 seconds
+#[derive(Debug, Clone)]
+pub struct TotpSettings {
+    algorithm: TotpAlgorithm,
+    digits: u32,
+    step_seconds: u64,
+    // Amount of steps accepted before and after the current one to tolerate clock drift
+    window: u64,
+}
+
+impl Default for TotpSettings {
+    fn default() -> Self {
+        Self {
+            algorithm: TotpAlgorithm::Sha1,
+            digits: 6,
+            step_seconds: 30,
+            window: 1,
+        }
+    }
+}
+
+impl TotpSettings {
+    pub const MIN_DIGITS: u32 = 6;
+    // Codes are computed in u32
+    pub const MAX_DIGITS: u32 = 9;
+    pub const MAX_WINDOW: u64 = 10;
+
+    pub fn new(
+        algorithm: TotpAlgorithm,
+        digits: u32,
+        step_seconds: u64,
+        window: u64,
+    ) -> Result<Self, String> {
+        if !(Self::MIN_DIGITS..=Self::MAX_DIGITS).contains(&digits) {
+            return Err(format!(
+                "Totp digits must be from {} to {}. Got: {}",
+                Self::MIN_DIGITS,
+                Self::MAX_DIGITS,
+                digits
+            ));
+        }
+
+        if step_seconds == 0 {
+            return Err("Totp step_seconds must be greater than 0".to_string());
+        }
+
+        if window > Self::MAX_WINDOW {
+            return Err(format!(
+                "Totp window must not be greater than {}. Got: {}",
+                Self::MAX_WINDOW,
+                window
+            ));
+        }
+
+        Ok(Self {
+            algorithm,
+            digits,
+            step_seconds,
+            window,
+        })
+    }
+
+    pub fn get_algorithm(&self) -> TotpAlgorithm {
+        self.algorithm
+    }
+
+    pub fn get_digits(&self) -> u32 {
+        self.digits
+    }
+
+    pub fn get_step_seconds(&self) -> u64 {
+        self.step_seconds
+    }
+
+    pub fn get_window(&self) -> u64 {
+        self.window
+    }
+
+    pub fn get_counter(&self, unix_seconds: u64) -> u64 {
+        unix_seconds / self.step_seconds
+    }
+
+    pub fn generate(&self, secret: &[u8], unix_seconds: u64) -> String {
+        self.generate_hotp(secret, self.get_counter(unix_seconds))
+    }
+
+    // RFC 4226
+    pub fn generate_hotp(&self, secret: &[u8], counter: u64) -> String {
+        let hash = match self.algorithm {
+            TotpAlgorithm::Sha1 => calc_hmac::<Hmac<Sha1>>(secret, counter),
+            TotpAlgorithm::Sha256 => calc_hmac::<Hmac<Sha256>>(secret, counter),
+            TotpAlgorithm::Sha512 => calc_hmac::<Hmac<Sha512>>(secret, counter),
+        };
+
+        let offset = (hash[hash.len() - 1] & 0x0f) as usize;
+
+        let binary = ((hash[offset] as u32 & 0x7f) << 24)
+            | ((hash[offset + 1] as u32) << 16)
+            | ((hash[offset + 2] as u32) << 8)
+            | (hash[offset + 3] as u32);
+
+        let code = binary % 10u32.pow(self.digits);
+
+        format!("{:0width$}", code, width = self.digits as usize)
+    }
+
+    // Returns the counter of the step the code matched, so a replay guard can burn it
+    pub fn verify(&self, secret: &[u8], code: &str, unix_seconds: u64) -> Option<u64> {
+        let code = code.trim();
+
+        if code.len() != self.digits as usize || !code.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+
+        let counter = self.get_counter(unix_seconds);
+        let from = counter.saturating_sub(self.window);
+        let to = counter.saturating_add(self.window);
+
+        (from..=to).find(|counter| {
+            let expected = self.generate_hotp(secret, *counter);
+            constant_time_eq(&expected, code)
+        })
+    }
+
+    pub fn get_otpauth_uri(&self, secret: &[u8], issuer: &str, account_name: &str) -> String {
+        format!(
+            "otpauth://totp/{}:{}?secret={}&issuer={}&algorithm={}&digits={}&period={}",
+            percent_encode(issuer),
+            percent_encode(account_name),
+            base32_encode(secret),
+            percent_encode(issuer),
+            self.algorithm.as_str(),
+            self.digits,
+            self.step_seconds
+        )
+    }
+}
+
+// 20 bytes is the RFC 4226 recommended secret length for SHA1
+pub fn generate_totp_secret() -> Vec<u8> {
+    let mut result = vec![0u8; 20];
+    OsRng.fill_bytes(&mut result);
+    result
+}
+
+pub fn parse_totp_secret(base32_secret: &str) -> Option<Vec<u8>> {
+    base32_decode(base32_secret)
+}
+
+fn calc_hmac<TMac: Mac + hmac::digest::KeyInit>(secret: &[u8], counter: u64) -> Vec<u8> {
+    let mut mac = <TMac as hmac::digest::KeyInit>::new_from_slice(secret).unwrap();
+    mac.update(&counter.to_be_bytes());
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn percent_encode(src: &str) -> String {
+    let mut result = String::with_capacity(src.len());
+
+    for b in src.bytes() {
+        if b.is_ascii_alphanumeric() || matches!(b, b'-' | b'.' | b'_' | b'~' | b'@') {
+            result.push(b as char);
+        } else {
+            result.push_str(&format!("%{:02X}", b));
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const SHA1_SECRET: &[u8] = b"12345678901234567890";
+    const SHA256_SECRET: &[u8] = b"12345678901234567890123456789012";
+    const SHA512_SECRET: &[u8] =
+        b"1234567890123456789012345678901234567890123456789012345678901234";
+
+    fn rfc6238_settings(algorithm: TotpAlgorithm) -> TotpSettings {
+        TotpSettings::new(algorithm, 8, 30, 0).unwrap()
+    }
+
+    #[test]
+    fn test_invalid_settings_are_rejected() {
+        assert!(TotpSettings::new(TotpAlgorithm::Sha1, 6, 0, 1).is_err());
+        assert!(TotpSettings::new(TotpAlgorithm::Sha1, 5, 30, 1).is_err());
+        assert!(TotpSettings::new(TotpAlgorithm::Sha1, 10, 30, 1).is_err());
+        assert!(TotpSettings::new(TotpAlgorithm::Sha1, 6, 30, 11).is_err());
+        assert!(TotpSettings::new(TotpAlgorithm::Sha1, 9, 30, 1).is_ok());
+    }
+
+    #[test]
+    fn test_rfc4226_hotp_vectors() {
+        let settings = TotpSettings::default();
+
+        let expected = [
+            "755224", "287082", "359152", "969429", "338314", "254676", "287922", "162583",
+            "399871", "520489",
+        ];
+
+        for (counter, expected) in expected.iter().enumerate() {
+            assert_eq!(
+                *expected,
+                settings.generate_hotp(SHA1_SECRET, counter as u64)
+            );
+        }
+    }
+
+    #[test]
+    fn test_rfc6238_vectors() {
+        let vectors: [(u64, &str, &str, &str); 6] = [
+            (59, "94287082", "46119246", "90693936"),
+            (1111111109, "07081804", "68084774", "25091201"),
+            (1111111111, "14050471", "67062674", "99943326"),
+            (1234567890, "89005924", "91819424", "93441116"),
+            (2000000000, "69279037", "90698825", "38618901"),
+            (20000000000, "65353130", "77737706", "47863826"),
+        ];
+
+        let sha1 = rfc6238_settings(TotpAlgorithm::Sha1);
+        let sha256 = rfc6238_settings(TotpAlgorithm::Sha256);
+        let sha512 = rfc6238_settings(TotpAlgorithm::Sha512);
+
+        for (time, sha1_code, sha256_code, sha512_code) in vectors {
+            assert_eq!(sha1_code, sha1.generate(SHA1_SECRET, time));
+            assert_eq!(sha256_code, sha256.generate(SHA256_SECRET, time));
+            assert_eq!(sha512_code, sha512.generate(SHA512_SECRET, time));
+        }
+    }
+
+    #[test]
+    fn test_verify_window() {
+        let settings = TotpSettings::default();
+        let code = settings.generate(SHA1_SECRET, 1_000_000);
+        let counter = settings.get_counter(1_000_000);
+
+        assert_eq!(
+            Some(counter),
+            settings.verify(SHA1_SECRET, &code, 1_000_000)
+        );
+        assert_eq!(
+            Some(counter),
+            settings.verify(SHA1_SECRET, &code, 1_000_030)
+        );
+        assert_eq!(Some(counter), settings.verify(SHA1_SECRET, &code, 999_970));
+        assert_eq!(None, settings.verify(SHA1_SECRET, &code, 1_000_090));
+        assert_eq!(None, settings.verify(SHA1_SECRET, "12345", 1_000_000));
+        assert_eq!(None, settings.verify(SHA1_SECRET, "abcdef", 1_000_000));
+    }
+
+    #[test]
+    fn test_otpauth_uri() {
+        let settings = TotpSettings::default();
+
+        assert_eq!(
+            "otpauth://totp/My%20Wallet:trader@mail.com?secret=GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ&issuer=My%20Wallet&algorithm=SHA1&digits=6&period=30",
+            settings.get_otpauth_uri(SHA1_SECRET, "My Wallet", "trader@mail.com")
+        );
+    }
+
+    #[test]
+    fn test_generated_secret_roundtrip() {
+        let secret = generate_totp_secret();
+        assert_eq!(20, secret.len());
+        assert_eq!(
+            Some(secret.clone()),
+            parse_totp_secret(&base32_encode(&secret))
+        );
+    }
+}
@@ -0,0 +1,108 @@
+use std::sync::Arc;
+
+use service_sdk::rust_extensions::date_time::DateTimeAsMicroseconds;
+
+use crate::{step_up::SecondFactorVerifier, ApiResultStatus};
+
+use super::{TotpReplayGuard, TotpSettings};
+
+#[async_trait::async_trait]
+pub trait TotpSecretStore {
+    async fn get_totp_secret(&self, trader_id: &str) -> Option<Vec<u8>>;
+}
+
+// Used steps are remembered in memory of this instance (see TotpReplayGuard),
+// so a code replayed to another replica inside its step is accepted
+pub struct TotpVerifier {
+    secrets_store: Arc<dyn TotpSecretStore + Send + Sync + 'static>,
+    settings: TotpSettings,
+    replay_guard: TotpReplayGuard,
+}
+
+impl TotpVerifier {
+    pub fn new(
+        secrets_store: Arc<dyn TotpSecretStore + Send + Sync + 'static>,
+        settings: TotpSettings,
+    ) -> Self {
+        Self {
+            secrets_store,
+            settings,
+            replay_guard: TotpReplayGuard::new(),
+        }
+    }
+
+    pub fn get_settings(&self) -> &TotpSettings {
+        &self.settings
+    }
+
+    pub async fn verify_code(&self, trader_id: &str, code: &str) -> Result<(), ApiResultStatus> {
+        let secret = self
+            .secrets_store
+            .get_totp_secret(trader_id)
+            .await
+            .ok_or(ApiResultStatus::TwoFaCodeIsInvalid)?;
+
+        let now = (DateTimeAsMicroseconds::now().unix_microseconds / 1_000_000) as u64;
+
+        let counter = self
+            .settings
+            .verify(&secret, code, now)
+            .ok_or(ApiResultStatus::TwoFaCodeIsInvalid)?;
+
+        if !self.replay_guard.try_use(trader_id, counter) {
+            return Err(ApiResultStatus::TwoFaCodeIsInvalid);
+        }
+
+        self.replay_guard.gc(self
+            .settings
+            .get_counter(now)
+            .saturating_sub(self.settings.get_window() + 1));
+
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl SecondFactorVerifier for TotpVerifier {
+    fn get_factor_name(&self) -> &str {
+        "totp"
+    }
+
+    async fn verify(&self, trader_id: &str, code: &str) -> bool {
+        self.verify_code(trader_id, code).await.is_ok()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use service_sdk::rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use super::*;
+
+    const SECRET: &[u8] = b"12345678901234567890";
+
+    struct SingleSecretStore;
+
+    #[async_trait::async_trait]
+    impl TotpSecretStore for SingleSecretStore {
+        async fn get_totp_secret(&self, _trader_id: &str) -> Option<Vec<u8>> {
+            Some(SECRET.to_vec())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_code_is_rejected_on_replay() {
+        let verifier = TotpVerifier::new(Arc::new(SingleSecretStore), TotpSettings::default());
+
+        let now = (DateTimeAsMicroseconds::now().unix_microseconds / 1_000_000) as u64;
+        let code = verifier.get_settings().generate(SECRET, now);
+
+        assert!(verifier.verify_code("trader", &code).await.is_ok());
+        assert!(matches!(
+            verifier.verify_code("trader", &code).await,
+            Err(ApiResultStatus::TwoFaCodeIsInvalid)
+        ));
+    }
+}